@@ -0,0 +1,226 @@
+//! A heap with a fixed capacity, stored inline without allocations.
+//!
+//! See [`ArrayHeap`] for details.
+
+use core::{
+    ops::{Deref, DerefMut},
+    slice,
+};
+
+use crate::{ConstDefault, RawHeap, array_vec::ArrayVec, ordering::Ordering, raw_heap};
+
+/// A heap with a fixed capacity `N`, stored inline without allocations.
+///
+/// It is similar to [`VecHeap`], but the elements live in an array inside the heap itself.
+/// So it never allocates, and a full heap refuses new elements, see [`ArrayHeap::try_push`].
+///
+/// The constructors are `const`, so a heap can live in a `static`.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Mutex;
+/// use mheap::{ArrayHeap, MinHeap};
+///
+/// static TIMERS: Mutex<ArrayHeap<u64, MinHeap, 4>> = Mutex::new(ArrayHeap::new());
+///
+/// let mut timers = TIMERS.lock().unwrap();
+/// for deadline in [30, 10, 40, 20] {
+///     timers.try_push(deadline).unwrap();
+/// }
+/// assert_eq!(timers.try_push(50), Err(50));
+/// assert_eq!(timers.pop(), Some(10));
+/// assert_eq!(timers.peek(), Some(&20));
+/// ```
+///
+/// # Time complexity
+///
+/// | Operation  | Time complexity |
+/// |------------|----------------|
+/// | `try_push` | *O*(1)~        |
+/// | `pop`      | *O*(log(*n*))  |
+/// | `peek`     | *O*(1)         |
+///
+/// The value of `try_push` is an expected complexity.
+///
+/// [`VecHeap`]: crate::VecHeap
+pub struct ArrayHeap<T, O, const N: usize> {
+    data: ArrayVec<T, N>,
+    ord: O,
+}
+
+impl<T, O, const N: usize> ArrayHeap<T, O, N> {
+    /// Creates a new empty heap
+    pub const fn new() -> Self
+    where
+        O: ConstDefault,
+    {
+        Self::with_ordering(O::DEFAULT)
+    }
+
+    /// Creates a new empty heap with the specified ordering.
+    pub const fn with_ordering(ord: O) -> Self {
+        Self {
+            data: ArrayVec::new(),
+            ord,
+        }
+    }
+
+    /// Returns the number of elements in the heap.
+    pub const fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Returns `true` if the heap is empty.
+    pub const fn is_empty(&self) -> bool {
+        self.data.len() == 0
+    }
+
+    /// Returns `true` if the heap holds `N` elements, so no more can be pushed.
+    pub const fn is_full(&self) -> bool {
+        self.data.len() == N
+    }
+
+    /// Returns the number of elements the heap can hold, i.e. `N`.
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Removes all elements from the heap.
+    pub fn clear(&mut self) {
+        self.data.clear();
+    }
+
+    /// Returns an iterator visiting all elements, in arbitrary order.
+    pub fn iter(&self) -> slice::Iter<'_, T> {
+        self.data.as_slice().iter()
+    }
+
+    /// Returns a slice of all elements, in arbitrary order.
+    pub fn as_slice(&self) -> &[T] {
+        self.data.as_slice()
+    }
+}
+
+impl<T, O: Ordering<T>, const N: usize> ArrayHeap<T, O, N> {
+    /// Returns the top element, or `None` if the heap is empty.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(1)
+    pub fn peek(&self) -> Option<&T> {
+        self.data.peek()
+    }
+
+    /// Returns a mutable reference to the top element, or `None` if the heap is empty.
+    ///
+    /// The heap is restored, when the [`PeekMut`] is dropped.
+    /// See [`VecHeap::peek_mut`] for details.
+    ///
+    /// # Time complexity
+    ///
+    /// If the item is modified then the worst case time complexity is *O*(log(*n*)),
+    /// otherwise it's *O*(1).
+    ///
+    /// [`VecHeap::peek_mut`]: crate::VecHeap::peek_mut
+    pub fn peek_mut(&mut self) -> Option<PeekMut<'_, T, O, N>> {
+        RawHeap::peek_mut(&mut self.data).map(|raw| PeekMut {
+            raw,
+            ord: &self.ord,
+        })
+    }
+
+    /// Pushes an item onto the heap, or returns it back, if the heap is full.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mheap::{ArrayHeap, MaxHeap};
+    ///
+    /// let mut heap = ArrayHeap::<i32, MaxHeap, 2>::new();
+    /// assert_eq!(heap.try_push(1), Ok(()));
+    /// assert_eq!(heap.try_push(5), Ok(()));
+    /// assert_eq!(heap.try_push(3), Err(3));
+    /// assert_eq!(heap.peek(), Some(&5));
+    /// ```
+    ///
+    /// # Time complexity
+    ///
+    /// The expected cost of `try_push` is *O*(1), and the worst case is *O*(log(*n*)).
+    pub fn try_push(&mut self, item: T) -> Result<(), T> {
+        let pos = self.data.len();
+        self.data.try_push(item)?;
+        self.data.sift_up(pos, &self.ord);
+        Ok(())
+    }
+
+    /// Removes the top element from the heap and returns it, or `None` if it is empty.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(log(*n*))
+    pub fn pop(&mut self) -> Option<T> {
+        let item = self.data.pop()?;
+        Some(self.data.pop_swap(item, &self.ord))
+    }
+}
+
+impl<T, O: Default, const N: usize> Default for ArrayHeap<T, O, N> {
+    /// Creates an empty heap.
+    fn default() -> Self {
+        Self::with_ordering(O::default())
+    }
+}
+
+impl<'a, T, O, const N: usize> IntoIterator for &'a ArrayHeap<T, O, N> {
+    type Item = &'a T;
+    type IntoIter = slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Structure wrapping a mutable reference to the top item on an [`ArrayHeap`].
+///
+/// This `struct` is created by [`ArrayHeap::peek_mut`].
+pub struct PeekMut<'a, T, O: Ordering<T>, const N: usize> {
+    raw: raw_heap::PeekMut<'a, ArrayVec<T, N>>,
+    ord: &'a O,
+}
+
+impl<T, O: Ordering<T>, const N: usize> Drop for PeekMut<'_, T, O, N> {
+    fn drop(&mut self) {
+        self.raw.restore(self.ord);
+    }
+}
+
+impl<T, O: Ordering<T>, const N: usize> Deref for PeekMut<'_, T, O, N> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        self.raw.item()
+    }
+}
+
+impl<T, O: Ordering<T>, const N: usize> DerefMut for PeekMut<'_, T, O, N> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.raw.item_mut()
+    }
+}
+
+impl<T, O: Ordering<T>, const N: usize> PeekMut<'_, T, O, N> {
+    /// Removes the peeked value from the heap and returns it.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(log(*n*))
+    pub fn pop(mut self) -> T {
+        // We don't care if the element was mutated, as we will remove in the next line
+        self.raw.ignore_mutation();
+
+        let heap = self.raw.heap_mut();
+        let item = heap.pop().unwrap();
+        heap.pop_swap(item, self.ord)
+    }
+}
@@ -0,0 +1,109 @@
+use core::{
+    mem::{ManuallyDrop, MaybeUninit},
+    ptr, slice,
+};
+
+use crate::{Position, storage::Storage};
+
+/// A vector with an inline buffer of a fixed capacity `N`
+pub(crate) struct ArrayVec<T, const N: usize> {
+    // Invariant: the first `len` elements are initialized
+    data: [MaybeUninit<T>; N],
+    len: usize,
+}
+
+impl<T, const N: usize> ArrayVec<T, N> {
+    pub(crate) const fn new() -> Self {
+        Self {
+            data: [const { MaybeUninit::uninit() }; N],
+            len: 0,
+        }
+    }
+
+    pub(crate) const fn len(&self) -> usize {
+        self.len
+    }
+
+    pub(crate) fn as_slice(&self) -> &[T] {
+        // SAFETY: the first `len` elements are initialized
+        unsafe { slice::from_raw_parts(self.data.as_ptr().cast(), self.len) }
+    }
+
+    pub(crate) fn as_mut_slice(&mut self) -> &mut [T] {
+        // SAFETY: the first `len` elements are initialized
+        unsafe { slice::from_raw_parts_mut(self.data.as_mut_ptr().cast(), self.len) }
+    }
+
+    /// Returns the item back, if the vector is full
+    pub(crate) fn try_push(&mut self, item: T) -> Result<(), T> {
+        if self.len == N {
+            return Err(item);
+        }
+        self.data[self.len].write(item);
+        self.len += 1;
+        Ok(())
+    }
+
+    pub(crate) fn pop(&mut self) -> Option<T> {
+        self.len = self.len.checked_sub(1)?;
+        // SAFETY: the element was initialized, and it is no longer counted by `len`
+        Some(unsafe { self.data[self.len].assume_init_read() })
+    }
+
+    pub(crate) fn clear(&mut self) {
+        let elements: *mut [T] = self.as_mut_slice();
+        // Forget the elements first, so a panicking destructor cannot cause a double drop
+        self.len = 0;
+        // SAFETY: the elements are initialized, and no longer reachable
+        unsafe { ptr::drop_in_place(elements) };
+    }
+}
+
+impl<T, const N: usize> Drop for ArrayVec<T, N> {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+unsafe impl<T, const N: usize> Storage for ArrayVec<T, N> {
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    type Item = T;
+
+    type Key = T;
+
+    fn key(item: &Self::Item) -> &Self::Key {
+        item
+    }
+
+    fn get(&self, pos: Position) -> &Self::Item {
+        Storage::get(self.as_slice(), pos)
+    }
+
+    fn get_mut(&mut self, pos: Position) -> &mut Self::Item {
+        Storage::get_mut(self.as_mut_slice(), pos)
+    }
+
+    type Slot = T;
+
+    fn slot_key(item: &Self::Slot) -> &Self::Key {
+        item
+    }
+
+    unsafe fn load(&self, pos: Position) -> ManuallyDrop<Self::Slot> {
+        // SAFETY: forwards to the initialized part
+        unsafe { self.as_slice().load(pos) }
+    }
+
+    unsafe fn store(&mut self, pos: Position, item: &mut ManuallyDrop<Self::Slot>) {
+        // SAFETY: forwards to the initialized part
+        unsafe { self.as_mut_slice().store(pos, item) }
+    }
+
+    unsafe fn move_element(&mut self, src: Position, dst: Position) {
+        // SAFETY: forwards to the initialized part
+        unsafe { self.as_mut_slice().move_element(src, dst) }
+    }
+}
@@ -0,0 +1,296 @@
+//! A heap with a fixed capacity, that keeps only the best elements.
+//!
+//! See [`BoundedHeap`] for details.
+
+use alloc::vec::Vec;
+use core::{cmp, mem};
+
+use crate::{
+    MinMaxHeap,
+    min_max_heap::{self, PeekMut},
+    ordering::{Cmp, Ordering},
+};
+
+/// What a [`BoundedHeap`] does, when a new element is offered at full capacity.
+///
+/// The heap, together with the new element, holds one element too many.
+/// The policy selects which of them is dropped.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Policy {
+    /// Drops the new element.
+    RejectNew,
+    /// Drops the worst element. So the heap keeps the best `capacity` elements it was offered.
+    #[default]
+    EvictWorst,
+    /// Drops the best element. So the heap keeps the worst `capacity` elements it was offered.
+    EvictBest,
+}
+
+/// A heap with a fixed capacity, that keeps only the best elements.
+///
+/// A typical use is to select the top *k* elements of a stream:
+/// each element is [`offer`]ed to the heap, which admits it only if it is better than
+/// the worst element kept so far, see [`threshold`].
+/// What happens at full capacity is configured with a [`Policy`].
+///
+/// Use the `O` generic parameter to select what the *best* means.
+/// With [`MaxHeap`] the largest elements are kept, and with [`MinHeap`] the smallest ones.
+///
+/// # Examples
+///
+/// ```
+/// use mheap::{BoundedHeap, MaxHeap};
+///
+/// let mut top = BoundedHeap::<_, MaxHeap>::new(3);
+/// for score in [5, 1, 8, 3, 9, 2, 7] {
+///     top.offer(score);
+/// }
+/// assert_eq!(top.threshold(), Some(&7));
+/// assert_eq!(top.into_sorted_vec(), [9, 8, 7]);
+/// ```
+///
+/// # Time complexity
+///
+/// | Operation   | Time complexity |
+/// |-------------|-----------------|
+/// | `offer`     | *O*(log(*k*))   |
+/// | `threshold` | *O*(1)          |
+///
+/// Where *k* is the capacity of the heap.
+///
+/// [`offer`]: BoundedHeap::offer
+/// [`threshold`]: BoundedHeap::threshold
+/// [`MaxHeap`]: crate::MaxHeap
+/// [`MinHeap`]: crate::MinHeap
+pub struct BoundedHeap<T, O> {
+    heap: MinMaxHeap<T, ByPriority<O>>,
+    capacity: usize,
+    policy: Policy,
+}
+
+/// Compares elements by their priority, so the best element is the largest.
+struct ByPriority<O>(O);
+
+impl<T, O: Ordering<T>> Cmp<T> for ByPriority<O> {
+    fn cmp(&self, a: &T, b: &T) -> cmp::Ordering {
+        if self.0.should_sift_up(a, b) {
+            cmp::Ordering::Greater
+        } else if self.0.should_sift_down(a, b) {
+            cmp::Ordering::Less
+        } else {
+            cmp::Ordering::Equal
+        }
+    }
+}
+
+impl<T, O> BoundedHeap<T, O> {
+    /// Creates a new empty heap, that keeps at most `capacity` elements.
+    ///
+    /// The default [`Policy::EvictWorst`] is used.
+    pub fn new(capacity: usize) -> Self
+    where
+        O: Default,
+    {
+        Self::with_ordering_and_policy(capacity, O::default(), Policy::default())
+    }
+
+    /// Creates a new empty heap, that keeps at most `capacity` elements, with the specified policy.
+    pub fn with_policy(capacity: usize, policy: Policy) -> Self
+    where
+        O: Default,
+    {
+        Self::with_ordering_and_policy(capacity, O::default(), policy)
+    }
+
+    /// Creates a new empty heap, that keeps at most `capacity` elements, with the specified ordering.
+    ///
+    /// The default [`Policy::EvictWorst`] is used.
+    pub fn with_ordering(capacity: usize, ord: O) -> Self {
+        Self::with_ordering_and_policy(capacity, ord, Policy::default())
+    }
+
+    /// Creates a new empty heap, that keeps at most `capacity` elements,
+    /// with the specified ordering and policy.
+    pub fn with_ordering_and_policy(capacity: usize, ord: O, policy: Policy) -> Self {
+        Self {
+            heap: MinMaxHeap::with_cmp(ByPriority(ord)),
+            capacity,
+            policy,
+        }
+    }
+
+    /// Returns the number of elements in the heap.
+    pub fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    /// Returns `true` if the heap is empty.
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+
+    /// Returns `true` if the heap holds `capacity` elements.
+    pub fn is_full(&self) -> bool {
+        self.heap.len() >= self.capacity
+    }
+
+    /// Returns the maximum number of elements the heap keeps.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the policy, applied at full capacity.
+    pub fn policy(&self) -> Policy {
+        self.policy
+    }
+
+    /// Removes all elements from the heap.
+    pub fn clear(&mut self) {
+        self.heap.clear();
+    }
+
+    /// Returns an iterator visiting all elements, in arbitrary order.
+    pub fn iter(&self) -> min_max_heap::Iter<'_, T> {
+        self.heap.iter()
+    }
+
+    /// Consumes the heap and returns the elements in arbitrary order.
+    pub fn into_vec(self) -> Vec<T> {
+        self.heap.into_vec()
+    }
+}
+
+impl<T, O: Ordering<T>> BoundedHeap<T, O> {
+    /// Offers an element to the heap.
+    ///
+    /// Returns the element, that was dropped, if any.
+    /// It is either the new element, or an evicted one, depending on the [`Policy`].
+    /// Until the heap is full, every element is admitted and `None` is returned.
+    ///
+    /// Elements of equal priority are never evicted in favour of the new one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mheap::{BoundedHeap, MinHeap};
+    ///
+    /// let mut heap = BoundedHeap::<_, MinHeap>::new(2);
+    /// assert_eq!(heap.offer(5), None);
+    /// assert_eq!(heap.offer(3), None);
+    /// assert_eq!(heap.offer(1), Some(5)); // 1 is better than 5
+    /// assert_eq!(heap.offer(4), Some(4)); // 4 is worse than both 1 and 3
+    /// ```
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(log(*k*))
+    pub fn offer(&mut self, item: T) -> Option<T> {
+        if !self.is_full() {
+            self.heap.push(item);
+            return None;
+        }
+
+        let Some(threshold) = self.threshold() else {
+            return Some(item);
+        };
+        // The threshold is evicted, only if the new element is strictly on the far side of it
+        let ord = self.heap.cmp().cmp(&item, threshold);
+        let evict = match self.policy {
+            Policy::RejectNew => false,
+            Policy::EvictWorst => ord.is_gt(),
+            Policy::EvictBest => ord.is_lt(),
+        };
+        if !evict {
+            return Some(item);
+        }
+        let mut threshold = self.threshold_mut().unwrap();
+        Some(mem::replace(&mut *threshold, item))
+    }
+
+    /// Returns the element a new one is compared against, when the heap is full.
+    ///
+    /// With [`Policy::EvictWorst`] it is the worst element, and a new element must be better to be admitted.
+    /// With [`Policy::EvictBest`] it is the best element, and a new element must be worse to be admitted.
+    ///
+    /// Returns `None`, if the heap is not full (so any element is admitted),
+    /// or if the policy is [`Policy::RejectNew`] (so no element is admitted).
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(1)
+    pub fn threshold(&self) -> Option<&T> {
+        if !self.is_full() {
+            return None;
+        }
+        match self.policy {
+            Policy::RejectNew => None,
+            Policy::EvictWorst => self.heap.peek_min(),
+            Policy::EvictBest => self.heap.peek_max(),
+        }
+    }
+
+    fn threshold_mut(&mut self) -> Option<PeekMut<'_, T, ByPriority<O>>> {
+        match self.policy {
+            Policy::RejectNew => None,
+            Policy::EvictWorst => self.heap.peek_min_mut(),
+            Policy::EvictBest => self.heap.peek_max_mut(),
+        }
+    }
+
+    /// Returns the best element, or `None` if the heap is empty.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(1)
+    pub fn peek_best(&self) -> Option<&T> {
+        self.heap.peek_max()
+    }
+
+    /// Returns the worst element, or `None` if the heap is empty.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(1)
+    pub fn peek_worst(&self) -> Option<&T> {
+        self.heap.peek_min()
+    }
+
+    /// Consumes the heap and returns a vector sorted from the best element to the worst.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mheap::{BoundedHeap, MinHeap};
+    ///
+    /// let mut heap = BoundedHeap::<_, MinHeap>::new(3);
+    /// heap.extend([4, 8, 1, 6, 2]);
+    /// assert_eq!(heap.into_sorted_vec(), [1, 2, 4]);
+    /// ```
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*k* log(*k*))
+    pub fn into_sorted_vec(self) -> Vec<T> {
+        let mut sorted = self.heap.into_sorted_vec();
+        sorted.reverse();
+        sorted
+    }
+}
+
+impl<T, O: Ordering<T>> Extend<T> for BoundedHeap<T, O> {
+    /// Offers all elements to the heap, discarding the dropped ones.
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for item in iter {
+            self.offer(item);
+        }
+    }
+}
+
+impl<'a, T, O> IntoIterator for &'a BoundedHeap<T, O> {
+    type Item = &'a T;
+    type IntoIter = min_max_heap::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
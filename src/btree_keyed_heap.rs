@@ -0,0 +1,350 @@
+//! A priority queue, where elements are identified by an ordered key.
+//!
+//! See [`BTreeKeyedHeap`] for details.
+
+use alloc::collections::{BTreeMap, btree_map};
+use core::{borrow::Borrow, mem};
+
+use crate::{
+    indexable_heap::{Idx, IndexableHeap},
+    ordering::{ByPriority, Ordering},
+};
+
+/// A priority queue, where elements are identified by a key.
+///
+/// Each key is present in the queue at most once, together with its priority.
+/// The priority of a key can be looked up, changed or removed at any time.
+///
+/// The `O` generic parameter orders the priorities. Use [`MaxHeap`] or [`MinHeap`].
+/// The keys are looked up via [`Ord`].
+///
+/// It has the same API as `KeyedHeap`, but keeps a [`BTreeMap`] from keys to [`Idx`] on the side, instead of a `HashMap`.
+/// So it needs only `alloc`, and is available without the `std` feature.
+/// Lookups by key take *O*(log(*n*)) instead of *O*(1).
+///
+/// # Examples
+///
+/// ```
+/// use mheap::{BTreeKeyedHeap, MinHeap};
+///
+/// let mut queue = BTreeKeyedHeap::<&str, u32, MinHeap>::new();
+/// queue.push_or_update("a", 10);
+/// queue.push_or_update("b", 5);
+/// queue.push_or_update("c", 7);
+///
+/// // Change the priority of an existing key
+/// assert_eq!(queue.push_or_update("a", 1), Some(10));
+/// assert_eq!(queue.priority_of("a"), Some(&1));
+///
+/// assert_eq!(queue.remove("c"), Some(7));
+/// assert_eq!(queue.pop(), Some(("a", 1)));
+/// assert_eq!(queue.pop(), Some(("b", 5)));
+/// assert_eq!(queue.pop(), None);
+/// ```
+///
+/// # Time complexity
+///
+/// | Operation        | Time complexity |
+/// |------------------|-----------------|
+/// | `push_or_update` | *O*(log(*n*))   |
+/// | `pop`            | *O*(log(*n*))   |
+/// | `remove`         | *O*(log(*n*))   |
+/// | `peek`           | *O*(1)          |
+/// | `priority_of`    | *O*(log(*n*))   |
+///
+/// [`MaxHeap`]: crate::MaxHeap
+/// [`MinHeap`]: crate::MinHeap
+/// [`BTreeMap`]: alloc::collections::BTreeMap
+pub struct BTreeKeyedHeap<K, P, O> {
+    heap: IndexableHeap<(K, P), ByPriority<O>>,
+    index: BTreeMap<K, Idx<(K, P)>>,
+}
+
+impl<K, P, O> BTreeKeyedHeap<K, P, O> {
+    /// Creates a new empty queue.
+    pub fn new() -> Self
+    where
+        O: Default,
+    {
+        Self::with_ordering(O::default())
+    }
+
+    /// Creates a new empty queue with the specified ordering of priorities.
+    pub fn with_ordering(ord: O) -> Self {
+        Self {
+            heap: IndexableHeap::with_ordering(ByPriority(ord)),
+            index: BTreeMap::new(),
+        }
+    }
+
+    /// Returns the number of keys in the queue.
+    pub fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    /// Returns `true` if the queue is empty.
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+}
+
+impl<K: Ord + Clone, P, O: Ordering<P>> BTreeKeyedHeap<K, P, O> {
+    /// Returns `true` if the queue contains the key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use mheap::{BTreeKeyedHeap, MaxHeap};
+    ///
+    /// let mut queue = BTreeKeyedHeap::<&str, u32, MaxHeap>::new();
+    /// queue.push_or_update("a", 10);
+    /// assert!(queue.contains_key("a"));
+    /// assert!(!queue.contains_key("b"));
+    /// ```
+    pub fn contains_key<Q: Ord + ?Sized>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+    {
+        self.index.contains_key(key)
+    }
+
+    /// Returns the priority of the key, or `None` if the key is not in the queue.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(log(*n*))
+    pub fn priority_of<Q: Ord + ?Sized>(&self, key: &Q) -> Option<&P>
+    where
+        K: Borrow<Q>,
+    {
+        let index = *self.index.get(key)?;
+        self.heap.get(index).map(|(_, priority)| priority)
+    }
+
+    /// Returns the key with the top priority, or `None` if the queue is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use mheap::{BTreeKeyedHeap, MaxHeap};
+    ///
+    /// let mut queue = BTreeKeyedHeap::<&str, u32, MaxHeap>::new();
+    /// assert_eq!(queue.peek(), None);
+    ///
+    /// queue.push_or_update("a", 10);
+    /// queue.push_or_update("b", 20);
+    /// assert_eq!(queue.peek(), Some((&"b", &20)));
+    /// ```
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(1)
+    pub fn peek(&self) -> Option<(&K, &P)> {
+        self.heap.peek().map(|(key, priority)| (key, priority))
+    }
+
+    /// Inserts the key with the given priority,
+    /// or changes the priority if the key is already in the queue.
+    ///
+    /// Returns the old priority, if the key was present.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(log(*n*))
+    pub fn push_or_update(&mut self, key: K, priority: P) -> Option<P> {
+        match self.entry(key) {
+            Entry::Occupied(mut entry) => Some(entry.set_priority(priority)),
+            Entry::Vacant(entry) => {
+                entry.insert(priority);
+                None
+            }
+        }
+    }
+
+    /// Removes the key with the top priority, and returns it together with its priority.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(log(*n*))
+    pub fn pop(&mut self) -> Option<(K, P)> {
+        let (key, priority) = self.heap.pop()?;
+        self.index.remove(&key);
+        Some((key, priority))
+    }
+
+    /// Removes the key from the queue, and returns its priority.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(log(*n*))
+    pub fn remove<Q: Ord + ?Sized>(&mut self, key: &Q) -> Option<P>
+    where
+        K: Borrow<Q>,
+    {
+        let index = self.index.remove(key)?;
+        self.heap.remove(index).map(|(_, priority)| priority)
+    }
+
+    /// Gets the given key's entry in the queue for in-place manipulation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use mheap::{BTreeKeyedHeap, MinHeap};
+    ///
+    /// let mut queue = BTreeKeyedHeap::<&str, u32, MinHeap>::new();
+    /// queue.push_or_update("a", 10);
+    ///
+    /// // Relax the distance only if it became shorter
+    /// for (key, dist) in [("a", 5), ("b", 7), ("a", 8)] {
+    ///     let mut entry = queue.entry(key).or_insert(dist);
+    ///     if dist < *entry.priority() {
+    ///         entry.set_priority(dist);
+    ///     }
+    /// }
+    /// assert_eq!(queue.pop(), Some(("a", 5)));
+    /// assert_eq!(queue.pop(), Some(("b", 7)));
+    /// ```
+    pub fn entry(&mut self, key: K) -> Entry<'_, K, P, O> {
+        match self.index.entry(key) {
+            btree_map::Entry::Occupied(entry) => Entry::Occupied(OccupiedEntry {
+                heap: &mut self.heap,
+                entry,
+            }),
+            btree_map::Entry::Vacant(entry) => Entry::Vacant(VacantEntry {
+                heap: &mut self.heap,
+                entry,
+            }),
+        }
+    }
+}
+
+impl<K, P, O: Default> Default for BTreeKeyedHeap<K, P, O> {
+    /// Creates an empty queue.
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A view into a single entry of a [`BTreeKeyedHeap`], which may either be vacant or occupied.
+///
+/// This `enum` is created by the [`entry`] method on [`BTreeKeyedHeap`].
+///
+/// [`entry`]: BTreeKeyedHeap::entry
+pub enum Entry<'a, K, P, O> {
+    Occupied(OccupiedEntry<'a, K, P, O>),
+    Vacant(VacantEntry<'a, K, P, O>),
+}
+
+impl<'a, K: Ord + Clone, P, O: Ordering<P>> Entry<'a, K, P, O> {
+    /// Returns the key of this entry.
+    pub fn key(&self) -> &K {
+        match self {
+            Entry::Occupied(entry) => entry.key(),
+            Entry::Vacant(entry) => entry.key(),
+        }
+    }
+
+    /// Inserts the key with the given priority, if it is not in the queue yet.
+    pub fn or_insert(self, priority: P) -> OccupiedEntry<'a, K, P, O> {
+        self.or_insert_with(|| priority)
+    }
+
+    /// Inserts the key with the priority computed by `f`, if it is not in the queue yet.
+    pub fn or_insert_with<F: FnOnce() -> P>(self, f: F) -> OccupiedEntry<'a, K, P, O> {
+        match self {
+            Entry::Occupied(entry) => entry,
+            Entry::Vacant(entry) => entry.insert(f()),
+        }
+    }
+
+    /// Modifies the priority of the key, if it is in the queue.
+    pub fn and_modify<F: FnOnce(&mut P)>(self, f: F) -> Self {
+        match self {
+            Entry::Occupied(mut entry) => {
+                entry.update(f);
+                Entry::Occupied(entry)
+            }
+            Entry::Vacant(entry) => Entry::Vacant(entry),
+        }
+    }
+}
+
+/// A view into an occupied entry in a [`BTreeKeyedHeap`]. It is part of the [`Entry`] enum.
+pub struct OccupiedEntry<'a, K, P, O> {
+    heap: &'a mut IndexableHeap<(K, P), ByPriority<O>>,
+    entry: btree_map::OccupiedEntry<'a, K, Idx<(K, P)>>,
+}
+
+impl<K: Ord + Clone, P, O: Ordering<P>> OccupiedEntry<'_, K, P, O> {
+    /// Returns the key of this entry.
+    pub fn key(&self) -> &K {
+        self.entry.key()
+    }
+
+    /// Returns the priority of this entry.
+    pub fn priority(&self) -> &P {
+        &self.heap.by_index(*self.entry.get()).1
+    }
+
+    /// Sets the priority of this entry, and returns the old one.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(log(*n*))
+    pub fn set_priority(&mut self, priority: P) -> P {
+        let mut element = self.heap.by_index_mut(*self.entry.get());
+        mem::replace(&mut element.1, priority)
+    }
+
+    /// Modifies the priority of this entry in place.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(log(*n*))
+    pub fn update<F: FnOnce(&mut P)>(&mut self, f: F) {
+        let mut element = self.heap.by_index_mut(*self.entry.get());
+        f(&mut element.1);
+    }
+
+    /// Removes the entry from the queue, and returns its key and priority.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(log(*n*))
+    pub fn remove(self) -> (K, P) {
+        let index = self.entry.remove();
+        self.heap.by_index_mut(index).remove()
+    }
+}
+
+/// A view into a vacant entry in a [`BTreeKeyedHeap`]. It is part of the [`Entry`] enum.
+pub struct VacantEntry<'a, K, P, O> {
+    heap: &'a mut IndexableHeap<(K, P), ByPriority<O>>,
+    entry: btree_map::VacantEntry<'a, K, Idx<(K, P)>>,
+}
+
+impl<'a, K: Ord + Clone, P, O: Ordering<P>> VacantEntry<'a, K, P, O> {
+    /// Returns the key of this entry.
+    pub fn key(&self) -> &K {
+        self.entry.key()
+    }
+
+    /// Takes ownership of the key.
+    pub fn into_key(self) -> K {
+        self.entry.into_key()
+    }
+
+    /// Inserts the key with the given priority into the queue.
+    ///
+    /// # Time complexity
+    ///
+    /// The same as [`IndexableHeap::push`].
+    pub fn insert(self, priority: P) -> OccupiedEntry<'a, K, P, O> {
+        let index = self.heap.push((self.entry.key().clone(), priority));
+        OccupiedEntry {
+            heap: self.heap,
+            entry: self.entry.insert_entry(index),
+        }
+    }
+}
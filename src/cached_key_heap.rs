@@ -0,0 +1,383 @@
+//! A heap, that computes the key of each element once, and compares the cached keys.
+//!
+//! See [`CachedKeyHeap`] for details.
+
+use alloc::vec::Vec;
+use core::{
+    iter::FusedIterator,
+    ops::{Deref, DerefMut},
+    slice,
+};
+
+use crate::{MaxHeap, MinHeap, RawHeap, ordering::Ordering, raw_heap};
+
+/// A heap, that computes the key of each element once, and compares the cached keys.
+///
+/// With [`MinHeap::by_key`] the key function is called twice on every comparison.
+/// A `CachedKeyHeap` calls it once on push, and stores the key next to the element.
+/// Use it, when the key is expensive to compute, e.g. parsed from a string.
+///
+/// The ordering is created by [`MaxHeap::by_cached_key`] or [`MinHeap::by_cached_key`].
+///
+/// # Examples
+///
+/// ```
+/// use mheap::{CachedKeyHeap, MinHeap};
+///
+/// let mut heap = CachedKeyHeap::with_ordering(MinHeap::by_cached_key(|s: &String| s.parse::<u32>().unwrap()));
+/// heap.push("20".to_string());
+/// heap.push("3".to_string());
+/// heap.push("100".to_string());
+///
+/// assert_eq!(heap.peek_key(), Some(&3));
+/// assert_eq!(heap.pop().as_deref(), Some("3"));
+/// assert_eq!(heap.pop().as_deref(), Some("20"));
+/// ```
+///
+/// # Time complexity
+///
+/// | Operation | Time complexity |
+/// |-----------|----------------|
+/// | `push`    | *O*(1)~        |
+/// | `pop`     | *O*(log(*n*))  |
+/// | `peek`    | *O*(1)         |
+///
+/// The value of `push` is an expected complexity.
+/// The key function is called once per `push`, and once per mutation through [`PeekMut`].
+///
+/// [`IndexableCachedKeyHeap`] also allows access to elements by index.
+///
+/// [`MaxHeap::by_cached_key`]: crate::MaxHeap::by_cached_key
+/// [`MinHeap::by_cached_key`]: crate::MinHeap::by_cached_key
+/// [`MinHeap::by_key`]: crate::MinHeap::by_key
+/// [`IndexableCachedKeyHeap`]: crate::IndexableCachedKeyHeap
+pub struct CachedKeyHeap<T, K, O> {
+    data: Vec<Cached<K, T>>,
+    ord: O,
+}
+
+/// An element of a [`CachedKeyHeap`], together with its cached key.
+///
+/// It is public only to allow its usage in trait bounds, see [`CachedKey`].
+pub struct Cached<K, T> {
+    pub(crate) key: K,
+    pub(crate) item: T,
+}
+
+/// Orders [`Cached`] elements by their cached keys, and computes the keys with a key function.
+///
+/// Use it via [`MaxHeap::by_cached_key`] and [`MinHeap::by_cached_key`], together with a [`CachedKeyHeap`].
+pub struct CachedKey<O, F> {
+    ord: O,
+    key: F,
+}
+
+impl<O, F> CachedKey<O, F> {
+    pub(crate) fn key<T, K>(&self, item: &T) -> K
+    where
+        F: Fn(&T) -> K,
+    {
+        (self.key)(item)
+    }
+}
+
+impl<K, T, O: Ordering<K>, F> Ordering<Cached<K, T>> for CachedKey<O, F> {
+    fn should_sift_up(&self, elt: &Cached<K, T>, parent: &Cached<K, T>) -> bool {
+        self.ord.should_sift_up(&elt.key, &parent.key)
+    }
+    fn should_sift_down(&self, elt: &Cached<K, T>, child: &Cached<K, T>) -> bool {
+        self.ord.should_sift_down(&elt.key, &child.key)
+    }
+}
+
+impl MaxHeap {
+    /// Creates an ordering for a [`CachedKeyHeap`], that computes the key of each element once, on push.
+    ///
+    /// Unlike [`MaxHeap::by_key`], the key function is not called on every comparison.
+    /// Use it, when the key is expensive to compute. Elements with larger keys will be prioritized.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mheap::{CachedKeyHeap, MaxHeap};
+    ///
+    /// let mut heap = CachedKeyHeap::with_ordering(MaxHeap::by_cached_key(|s: &&str| s.len()));
+    /// heap.push("bb");
+    /// heap.push("a");
+    /// heap.push("ccc");
+    /// assert_eq!(heap.pop(), Some("ccc"));
+    /// ```
+    pub fn by_cached_key<T, K: Ord, F: Fn(&T) -> K>(key: F) -> CachedKey<MaxHeap, F> {
+        CachedKey { ord: MaxHeap::natural(), key }
+    }
+}
+
+impl MinHeap {
+    /// Creates an ordering for a [`CachedKeyHeap`], that computes the key of each element once, on push.
+    ///
+    /// Unlike [`MinHeap::by_key`], the key function is not called on every comparison.
+    /// Use it, when the key is expensive to compute. Elements with smaller keys will be prioritized.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mheap::{CachedKeyHeap, MinHeap};
+    ///
+    /// let mut heap = CachedKeyHeap::with_ordering(MinHeap::by_cached_key(|s: &&str| s.len()));
+    /// heap.push("bb");
+    /// heap.push("a");
+    /// heap.push("ccc");
+    /// assert_eq!(heap.pop(), Some("a"));
+    /// ```
+    pub fn by_cached_key<T, K: Ord, F: Fn(&T) -> K>(key: F) -> CachedKey<MinHeap, F> {
+        CachedKey { ord: MinHeap::natural(), key }
+    }
+}
+
+impl<T, K, O> CachedKeyHeap<T, K, O> {
+    /// Creates a new empty heap with the specified ordering.
+    pub const fn with_ordering(ord: O) -> Self {
+        Self {
+            data: Vec::new(),
+            ord,
+        }
+    }
+
+    /// Creates a new empty heap with the specified capacity and ordering.
+    ///
+    /// The heap will be able to hold at least `capacity` elements without reallocating.
+    pub fn with_capacity_and_ordering(capacity: usize, ord: O) -> Self {
+        Self {
+            data: Vec::with_capacity(capacity),
+            ord,
+        }
+    }
+
+    /// Returns the number of elements in the heap.
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Returns `true` if the heap is empty.
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Returns the number of elements the heap can hold without reallocating.
+    pub fn capacity(&self) -> usize {
+        self.data.capacity()
+    }
+
+    /// Removes all elements from the heap.
+    pub fn clear(&mut self) {
+        self.data.clear();
+    }
+
+    /// Returns an iterator visiting all elements, in arbitrary order.
+    pub fn iter(&self) -> Iter<'_, K, T> {
+        Iter {
+            iter: self.data.iter(),
+        }
+    }
+
+    /// Consumes the heap and returns the elements in arbitrary order.
+    pub fn into_vec(self) -> Vec<T> {
+        self.data.into_iter().map(|it| it.item).collect()
+    }
+}
+
+impl<T, K, O: Ordering<K>, F: Fn(&T) -> K> CachedKeyHeap<T, K, CachedKey<O, F>> {
+    /// Returns the top element, or `None` if the heap is empty.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(1)
+    pub fn peek(&self) -> Option<&T> {
+        self.data.peek().map(|it| &it.item)
+    }
+
+    /// Returns the cached key of the top element, or `None` if the heap is empty.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(1)
+    pub fn peek_key(&self) -> Option<&K> {
+        self.data.peek().map(|it| &it.key)
+    }
+
+    /// Returns a mutable reference to the top element, or `None` if the heap is empty.
+    ///
+    /// If the element is mutated, its key is computed again, when the [`PeekMut`] is dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mheap::{CachedKeyHeap, MaxHeap};
+    ///
+    /// let mut heap = CachedKeyHeap::with_ordering(MaxHeap::by_cached_key(|v: &Vec<u32>| v.iter().sum::<u32>()));
+    /// heap.push(vec![1, 2]);
+    /// heap.push(vec![5]);
+    ///
+    /// if let Some(mut top) = heap.peek_mut() {
+    ///     top.clear();
+    /// }
+    /// assert_eq!(heap.peek_key(), Some(&3));
+    /// ```
+    ///
+    /// # Time complexity
+    ///
+    /// If the item is modified then the worst case time complexity is *O*(log(*n*)),
+    /// otherwise it's *O*(1).
+    pub fn peek_mut(&mut self) -> Option<PeekMut<'_, T, K, O, F>> {
+        RawHeap::peek_mut(&mut self.data).map(|raw| PeekMut {
+            raw,
+            ord: &self.ord,
+            mutated: false,
+        })
+    }
+
+    /// Pushes an element onto the heap, computing its key.
+    ///
+    /// # Time complexity
+    ///
+    /// The expected cost of `push` is *O*(1), and the worst case is *O*(log(*n*)).
+    pub fn push(&mut self, item: T) {
+        let key = self.ord.key(&item);
+        let pos = self.data.len();
+        self.data.push(Cached { key, item });
+        self.data.sift_up(pos, &self.ord);
+    }
+
+    /// Removes the top element from the heap and returns it, or `None` if it is empty.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(log(*n*))
+    pub fn pop(&mut self) -> Option<T> {
+        let last = self.data.pop()?;
+        Some(self.data.pop_swap(last, &self.ord).item)
+    }
+
+    /// Consumes the heap and returns a vector in the pop order.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*n* log(*n*))
+    pub fn into_sorted_vec(mut self) -> Vec<T> {
+        let mut sorted = Vec::with_capacity(self.len());
+        while let Some(item) = self.pop() {
+            sorted.push(item);
+        }
+        sorted
+    }
+}
+
+impl<T, K, O: Ordering<K>, F: Fn(&T) -> K> Extend<T> for CachedKeyHeap<T, K, CachedKey<O, F>> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for item in iter {
+            self.push(item);
+        }
+    }
+}
+
+impl<'a, T, K, O> IntoIterator for &'a CachedKeyHeap<T, K, O> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, K, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// An iterator over the elements of a [`CachedKeyHeap`], in arbitrary order.
+///
+/// This `struct` is created by [`CachedKeyHeap::iter`].
+pub struct Iter<'a, K, T> {
+    iter: slice::Iter<'a, Cached<K, T>>,
+}
+
+impl<K, T> Clone for Iter<'_, K, T> {
+    fn clone(&self) -> Self {
+        Self {
+            iter: self.iter.clone(),
+        }
+    }
+}
+
+impl<'a, K, T> Iterator for Iter<'a, K, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().map(|it| &it.item)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<K, T> DoubleEndedIterator for Iter<'_, K, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.iter.next_back().map(|it| &it.item)
+    }
+}
+
+impl<K, T> ExactSizeIterator for Iter<'_, K, T> {}
+
+impl<K, T> FusedIterator for Iter<'_, K, T> {}
+
+/// Structure wrapping a mutable reference to the top item on a [`CachedKeyHeap`].
+///
+/// This `struct` is created by [`CachedKeyHeap::peek_mut`].
+/// If the item was mutated, its key is computed again on drop.
+/// If the key function panics there, the item keeps its old key and its position,
+/// so the heap stays ordered by the cached keys.
+pub struct PeekMut<'a, T, K, O: Ordering<K>, F: Fn(&T) -> K> {
+    raw: raw_heap::PeekMut<'a, Vec<Cached<K, T>>>,
+    ord: &'a CachedKey<O, F>,
+    mutated: bool,
+}
+
+impl<T, K, O: Ordering<K>, F: Fn(&T) -> K> Drop for PeekMut<'_, T, K, O, F> {
+    fn drop(&mut self) {
+        if self.mutated {
+            // Compute the key before touching the heap. If the key function panics,
+            // the element keeps its old key, which is still in order at the root.
+            let key = self.ord.key(&self.raw.item().item);
+            self.raw.item_mut().key = key;
+        }
+        self.raw.restore(self.ord);
+    }
+}
+
+impl<T, K, O: Ordering<K>, F: Fn(&T) -> K> Deref for PeekMut<'_, T, K, O, F> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.raw.item().item
+    }
+}
+
+impl<T, K, O: Ordering<K>, F: Fn(&T) -> K> DerefMut for PeekMut<'_, T, K, O, F> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.mutated = true;
+        &mut self.raw.item_mut().item
+    }
+}
+
+impl<T, K, O: Ordering<K>, F: Fn(&T) -> K> PeekMut<'_, T, K, O, F> {
+    /// Removes the peeked value from the heap and returns it.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(log(*n*))
+    pub fn pop(mut self) -> T {
+        // The element is removed, so neither its key nor its position matter
+        self.mutated = false;
+        self.raw.ignore_mutation();
+
+        let heap = self.raw.heap_mut();
+        let last = heap.pop().unwrap();
+        heap.pop_swap(last, self.ord).item
+    }
+}
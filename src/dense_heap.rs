@@ -0,0 +1,319 @@
+//! A heap of elements identified by small integer ids, chosen by the caller.
+//!
+//! See [`DenseIndexedHeap`] for details.
+
+use core::mem;
+
+use crate::{ConstDefault, Position, RawHeap, dense_vec::DenseVec, ordering::Ordering};
+
+pub use crate::dense_vec::Id;
+
+/// A heap of elements identified by small integer ids, chosen by the caller.
+///
+/// Like [`IndexableHeap`], it allows to access and modify elements after they were pushed.
+/// But instead of issuing opaque handles, it uses ids in range `0..n`, provided by the caller.
+/// This is a natural fit for graph algorithms (like Dijkstra or Prim), where the elements are vertices.
+///
+/// The positions of elements are tracked in a plain table indexed by id,
+/// so its memory usage is proportional to the largest id, not to the number of elements.
+///
+/// Use the `O` generic parameter to select [`MaxHeap`] or [`MinHeap`].
+///
+/// # Examples
+///
+/// ```
+/// use mheap::{DenseIndexedHeap, MinHeap};
+///
+/// let mut heap = DenseIndexedHeap::<u32, MinHeap>::with_capacity(4);
+/// heap.push(0, 10);
+/// heap.push(1, 7);
+/// heap.push(3, 15);
+///
+/// heap.decrease_key(3, 2);
+/// assert_eq!(heap.pop(), Some((3, 2)));
+/// assert_eq!(heap.remove(0), Some(10));
+/// assert_eq!(heap.pop(), Some((1, 7)));
+/// assert_eq!(heap.pop(), None);
+/// ```
+///
+/// # Time complexity
+///
+/// | Operation      | Time complexity |
+/// |----------------|-----------------|
+/// | `push`         | *O*(1)~         |
+/// | `pop`          | *O*(log(*n*))   |
+/// | `peek`         | *O*(1)          |
+/// | `decrease_key` | *O*(log(*n*))   |
+/// | `remove`       | *O*(log(*n*))   |
+///
+/// The `push` operation has expected *O*(1) complexity.
+/// See [`IndexableHeap::push`] for details.
+///
+/// [`IndexableHeap`]: crate::IndexableHeap
+/// [`IndexableHeap::push`]: crate::IndexableHeap::push
+/// [`MaxHeap`]: crate::MaxHeap
+/// [`MinHeap`]: crate::MinHeap
+pub struct DenseIndexedHeap<T, O> {
+    data: DenseVec<T>,
+    ord: O,
+}
+
+impl<T, O> DenseIndexedHeap<T, O> {
+    /// Creates a new empty heap.
+    pub const fn new() -> Self
+    where
+        O: ConstDefault,
+    {
+        Self {
+            data: DenseVec::new(),
+            ord: O::DEFAULT,
+        }
+    }
+
+    /// Creates a new empty heap for ids in range `0..capacity`.
+    ///
+    /// The heap will be able to hold elements with such ids without reallocating.
+    pub fn with_capacity(capacity: usize) -> Self
+    where
+        O: Default,
+    {
+        Self {
+            data: DenseVec::with_capacity(capacity),
+            ord: O::default(),
+        }
+    }
+
+    /// Creates a new empty heap with the specified ordering.
+    pub const fn with_ordering(ord: O) -> Self {
+        Self {
+            data: DenseVec::new(),
+            ord,
+        }
+    }
+
+    /// Creates a new empty heap for ids in range `0..capacity` with the specified ordering.
+    ///
+    /// The heap will be able to hold elements with such ids without reallocating.
+    pub fn with_capacity_and_ordering(capacity: usize, ord: O) -> Self {
+        Self {
+            data: DenseVec::with_capacity(capacity),
+            ord,
+        }
+    }
+
+    /// Returns the number of elements in the heap.
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Returns the number of ids the heap can hold without reallocating.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use mheap::{DenseIndexedHeap, MinHeap};
+    ///
+    /// let heap = DenseIndexedHeap::<u32, MinHeap>::with_capacity(10);
+    /// assert_eq!(heap.capacity(), 10);
+    /// ```
+    pub fn capacity(&self) -> usize {
+        self.data.id_capacity()
+    }
+
+    /// Returns `true` if the heap is empty.
+    pub fn is_empty(&self) -> bool {
+        self.data.len() == 0
+    }
+
+    /// Removes all elements from the heap.
+    pub fn clear(&mut self) {
+        self.data.clear();
+    }
+
+    /// Returns `true` if the element with the given id is in the heap.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use mheap::{DenseIndexedHeap, MinHeap};
+    ///
+    /// let mut heap = DenseIndexedHeap::<u32, MinHeap>::with_capacity(10);
+    /// heap.push(3, 10);
+    /// assert!(heap.contains(3));
+    /// assert!(!heap.contains(4));
+    /// assert!(!heap.contains(100));
+    /// ```
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(1)
+    pub fn contains(&self, id: Id) -> bool {
+        self.data.id_to_pos(id).is_some()
+    }
+
+    /// Get a reference to the element with the given id, or `None` if it is not in the heap.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(1)
+    pub fn get(&self, id: Id) -> Option<&T> {
+        let pos = self.data.id_to_pos(id)?;
+        Some(self.data.get(pos))
+    }
+}
+
+impl<T, O: Ordering<T>> DenseIndexedHeap<T, O> {
+    /// Returns the top element in the heap together with its id, or `None` if it is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use mheap::{DenseIndexedHeap, MaxHeap};
+    ///
+    /// let mut heap = DenseIndexedHeap::<u32, MaxHeap>::new();
+    /// assert_eq!(heap.peek(), None);
+    ///
+    /// heap.push(0, 3);
+    /// heap.push(1, 5);
+    /// assert_eq!(heap.peek(), Some((1, &5)));
+    /// ```
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(1)
+    pub fn peek(&self) -> Option<(Id, &T)> {
+        self.data.peek()?;
+        Some(self.data.entry(0))
+    }
+
+    /// Pushes an element with the given id onto the heap.
+    ///
+    /// If the heap already contains an element with this id, it is replaced,
+    /// and the old element is returned.
+    ///
+    /// The heap grows its id table, if the id does not fit into the current [`capacity`].
+    /// The table has an entry for every id up to the largest one pushed,
+    /// so the memory usage is *O*(max id), not *O*(*n*). Keep the ids small and dense.
+    ///
+    /// # Panics
+    ///
+    /// If `id` is `usize::MAX`, or the id table cannot be allocated.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use mheap::{DenseIndexedHeap, MinHeap};
+    ///
+    /// let mut heap = DenseIndexedHeap::<u32, MinHeap>::new();
+    /// assert_eq!(heap.push(2, 10), None);
+    /// assert_eq!(heap.push(2, 5), Some(10));
+    /// assert_eq!(heap.len(), 1);
+    /// ```
+    ///
+    /// # Time complexity
+    ///
+    /// The same as [`IndexableHeap::push`].
+    ///
+    /// [`capacity`]: DenseIndexedHeap::capacity
+    /// [`IndexableHeap::push`]: crate::IndexableHeap::push
+    pub fn push(&mut self, id: Id, item: T) -> Option<T> {
+        if let Some(pos) = self.data.id_to_pos(id) {
+            let old = mem::replace(self.data.get_mut(pos), item);
+            self.data.fixup_sift(pos, &self.ord);
+            return Some(old);
+        }
+
+        let pos = self.data.len();
+        self.data.push(id, item);
+        self.data.sift_up(pos, &self.ord);
+        None
+    }
+
+    /// Removes the top element from the heap and returns it together with its id, or `None` if it is empty.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(log(*n*))
+    pub fn pop(&mut self) -> Option<(Id, T)> {
+        self.data.peek()?;
+        Some(self.remove_at(0))
+    }
+
+    /// Removes the element with the given id and returns it, or `None` if it is not in the heap.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(log(*n*))
+    pub fn remove(&mut self, id: Id) -> Option<T> {
+        let pos = self.data.id_to_pos(id)?;
+        Some(self.remove_at(pos).1)
+    }
+
+    /// Replaces an element with a value of higher (or equal) priority, moving it towards the top of the heap.
+    ///
+    /// Returns the old value, or `None` if the element is not in the heap.
+    ///
+    /// See [`IndexableHeap::decrease_key`] for details.
+    ///
+    /// # Panics
+    ///
+    /// In debug builds, if the new value has lower priority than the old one.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(log(*n*))
+    ///
+    /// [`IndexableHeap::decrease_key`]: crate::IndexableHeap::decrease_key
+    pub fn decrease_key(&mut self, id: Id, item: T) -> Option<T> {
+        let pos = self.data.id_to_pos(id)?;
+        debug_assert!(
+            !self.ord.should_sift_down(&item, self.data.get(pos)),
+            "decrease_key: the new value has lower priority than the old one"
+        );
+        let old = mem::replace(self.data.get_mut(pos), item);
+        self.data.sift_up(pos, &self.ord);
+        Some(old)
+    }
+
+    /// Replaces an element with a value of lower (or equal) priority, moving it towards the bottom of the heap.
+    ///
+    /// Returns the old value, or `None` if the element is not in the heap.
+    ///
+    /// See [`IndexableHeap::increase_key`] for details.
+    ///
+    /// # Panics
+    ///
+    /// In debug builds, if the new value has higher priority than the old one.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(log(*n*))
+    ///
+    /// [`IndexableHeap::increase_key`]: crate::IndexableHeap::increase_key
+    pub fn increase_key(&mut self, id: Id, item: T) -> Option<T> {
+        let pos = self.data.id_to_pos(id)?;
+        debug_assert!(
+            !self.ord.should_sift_up(&item, self.data.get(pos)),
+            "increase_key: the new value has higher priority than the old one"
+        );
+        let old = mem::replace(self.data.get_mut(pos), item);
+        self.data.sift_down(pos, &self.ord);
+        Some(old)
+    }
+
+    fn remove_at(&mut self, pos: Position) -> (Id, T) {
+        let entry = self.data.swap_remove(pos);
+        // In case it was the last element, we don't need to fix its position
+        if pos < self.data.len() {
+            self.data.fixup_sift_to_bottom(pos, &self.ord);
+        }
+        entry
+    }
+}
+
+impl<T, O: Default> Default for DenseIndexedHeap<T, O> {
+    /// Creates an empty heap.
+    fn default() -> Self {
+        Self::with_ordering(O::default())
+    }
+}
@@ -0,0 +1,132 @@
+use alloc::{vec, vec::Vec};
+use core::{mem::ManuallyDrop, ptr};
+
+use crate::Position;
+
+/// An identifier of an element of [`DenseIndexedHeap`], chosen by the caller
+///
+/// [`DenseIndexedHeap`]: crate::DenseIndexedHeap
+pub type Id = usize;
+
+/// A vector of elements, that tracks the position of each element by its dense [`Id`]
+pub(crate) struct DenseVec<T> {
+    data: Vec<(T, Id)>,
+    position: Vec<Option<Position>>,
+}
+
+impl<T> DenseVec<T> {
+    pub(crate) const fn new() -> Self {
+        Self {
+            data: Vec::new(),
+            position: Vec::new(),
+        }
+    }
+
+    pub(crate) fn with_capacity(capacity: usize) -> Self {
+        Self {
+            data: Vec::with_capacity(capacity),
+            position: vec![None; capacity],
+        }
+    }
+
+    pub(crate) const fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// The number of ids the position table can hold without reallocating
+    pub(crate) fn id_capacity(&self) -> usize {
+        self.position.len()
+    }
+
+    pub(crate) fn get(&self, pos: Position) -> &T {
+        &self.data[pos].0
+    }
+
+    pub(crate) fn get_mut(&mut self, pos: Position) -> &mut T {
+        &mut self.data[pos].0
+    }
+
+    pub(crate) fn entry(&self, pos: Position) -> (Id, &T) {
+        let (item, id) = &self.data[pos];
+        (*id, item)
+    }
+
+    /// Must not be called for an `id` already in use
+    pub(crate) fn push(&mut self, id: Id, item: T) {
+        if id >= self.position.len() {
+            let len = id.checked_add(1).expect("id is too large");
+            self.position.resize(len, None);
+        }
+        debug_assert!(self.position[id].is_none(), "id {id} is already in use");
+        let pos = self.data.len();
+        self.data.push((item, id));
+        self.position[id] = Some(pos);
+    }
+
+    pub(crate) fn swap_remove(&mut self, pos: Position) -> (Id, T) {
+        let (item, id) = self.data.swap_remove(pos);
+        self.position[id] = None;
+        // The last element was moved into the `pos`
+        if pos < self.data.len() {
+            self.record_position(pos);
+        }
+        (id, item)
+    }
+
+    pub(crate) fn clear(&mut self) {
+        for (_, id) in self.data.drain(..) {
+            self.position[id] = None;
+        }
+    }
+
+    pub(crate) fn id_to_pos(&self, id: Id) -> Option<Position> {
+        *self.position.get(id)?
+    }
+
+    fn record_position(&mut self, pos: Position) {
+        let id = self.data[pos].1;
+        self.position[id] = Some(pos);
+    }
+}
+
+unsafe impl<T> crate::storage::Storage for DenseVec<T> {
+    fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    type Item = T;
+
+    type Key = T;
+
+    fn key(item: &Self::Item) -> &Self::Key {
+        item
+    }
+
+    fn get(&self, pos: Position) -> &Self::Item {
+        self.get(pos)
+    }
+
+    fn get_mut(&mut self, pos: Position) -> &mut Self::Item {
+        self.get_mut(pos)
+    }
+
+    type Slot = (T, Id);
+
+    fn slot_key(item: &Self::Slot) -> &Self::Key {
+        &item.0
+    }
+
+    unsafe fn load(&self, pos: Position) -> ManuallyDrop<Self::Slot> {
+        ManuallyDrop::new(unsafe { ptr::read(&self.data[pos]) })
+    }
+
+    unsafe fn store(&mut self, pos: Position, item: &mut ManuallyDrop<Self::Slot>) {
+        unsafe { ptr::write(&mut self.data[pos], ManuallyDrop::take(item)) };
+        self.record_position(pos);
+    }
+
+    unsafe fn move_element(&mut self, src: Position, dst: Position) {
+        unsafe { ptr::copy_nonoverlapping(&self.data[src], &mut self.data[dst], 1) };
+        self.record_position(dst);
+    }
+}
@@ -1,4 +1,4 @@
-use std::mem::ManuallyDrop;
+use core::mem::ManuallyDrop;
 
 use crate::{Position, ordering::Ordering, storage::Storage, tree};
 
@@ -33,6 +33,8 @@ impl<S: Storage + ?Sized> Drop for Hole<'_, S> {
 impl<'a, S: Storage + ?Sized> Hole<'a, S> {
     /// Creates a new `Hole` at index `pos`.
     pub(crate) fn new(data: &'a mut S, pos: Position) -> Self {
+        // `load` does not check bounds by itself
+        assert!(pos < data.len(), "position {pos} is out of bounds");
         // SAFETY: by safety requirements on [`Storage`] trait
         //         the data does not have holes by default.
         //         We restore this hole in our Drop implementation.
@@ -57,7 +59,7 @@ impl<'a, S: Storage + ?Sized> Hole<'a, S> {
         index: Position,
         ord: &impl Ordering<S::Key>,
     ) -> bool {
-        if ord.should_sift_down(&self.element(), &self.data.get_key(index)) {
+        if ord.should_sift_down(self.element(), self.data.get_key(index)) {
             // SAFETY: guaranteed by the caller
             unsafe { self.move_to(index) };
             true
@@ -70,7 +72,7 @@ impl<'a, S: Storage + ?Sized> Hole<'a, S> {
         let Some(parent) = tree::parent(self.data, self.pos) else {
             return false;
         };
-        if ord.should_sift_up(&self.element(), &self.data.get_key(parent)) {
+        if ord.should_sift_up(self.element(), self.data.get_key(parent)) {
             // SAFETY: parent != pos by safety requirements on [`Storage`] trait
             unsafe { self.move_to(parent) };
             true
@@ -84,23 +86,22 @@ impl<'a, S: Storage + ?Sized> Hole<'a, S> {
             return None;
         }
 
+        // All `ARITY` children are present, so the loop has a constant trip count
         let first = tree::child(self.data, self.pos, 0).unwrap();
-        let second = tree::child(self.data, self.pos, 1).unwrap();
-        let cond = ord.select_upper(&self.data.get_key(first), &self.data.get_key(second));
-        Some(
-            if let Some(child) = tree::select_sibling(self.data, first, cond) {
-                child
-            } else {
-                if cond { first } else { second }
-            },
-        )
+        let mut upper = first;
+        for child in first + 1..first + S::ARITY {
+            if ord.select_upper(self.data.get_key(upper), self.data.get_key(child)) {
+                upper = child;
+            }
+        }
+        Some(upper)
     }
 
     pub(crate) fn upper_child_partial(&self, ord: &impl Ordering<S::Key>) -> Option<Position> {
         let mut children = tree::children(self.data, self.pos);
         let mut max = children.next()?;
         for child in children {
-            if ord.select_upper(&self.data.get_key(max), &self.data.get_key(child)) {
+            if ord.select_upper(self.data.get_key(max), self.data.get_key(child)) {
                 max = child;
             }
         }
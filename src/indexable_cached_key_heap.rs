@@ -0,0 +1,303 @@
+//! A [`CachedKeyHeap`], that allows access to elements by index.
+//!
+//! See [`IndexableCachedKeyHeap`] for details.
+//!
+//! [`CachedKeyHeap`]: crate::CachedKeyHeap
+
+use core::ops::{Deref, DerefMut};
+
+use crate::{
+    Position, RawHeap,
+    cached_key_heap::{Cached, CachedKey},
+    indexable_vec::{Idx, IndexableVec},
+    ordering::Ordering,
+};
+
+/// A [`CachedKeyHeap`], that allows access to elements by index.
+///
+/// Like [`IndexableHeap`], it issues an opaque [`Idx`] on push.
+/// The index can be used later to access, modify or remove the element.
+/// When an element is modified through [`GetMut`], its key is computed again.
+///
+/// The ordering is created by [`MaxHeap::by_cached_key`] or [`MinHeap::by_cached_key`].
+///
+/// # Examples
+///
+/// ```
+/// use mheap::{IndexableCachedKeyHeap, MinHeap};
+///
+/// let mut heap = IndexableCachedKeyHeap::with_ordering(MinHeap::by_cached_key(|s: &String| s.parse::<u32>().unwrap()));
+/// heap.push("20".to_string());
+/// let idx = heap.push("30".to_string());
+/// heap.push("100".to_string());
+///
+/// *heap.by_index_mut(idx) = "3".to_string();
+/// assert_eq!(heap.get_key(idx), Some(&3));
+/// assert_eq!(heap.pop().as_deref(), Some("3"));
+/// assert_eq!(heap.pop().as_deref(), Some("20"));
+/// ```
+///
+/// # Time complexity
+///
+/// | Operation            | Time complexity |
+/// |----------------------|-----------------|
+/// | `push`               | *O*(1)~         |
+/// | `pop`                | *O*(log(*n*))   |
+/// | `peek`, `get`        | *O*(1)          |
+/// | `get_mut`, `remove`  | *O*(log(*n*))   |
+///
+/// The value of `push` is an expected complexity.
+/// The key function is called once per `push`, and once per mutation through [`GetMut`].
+///
+/// [`CachedKeyHeap`]: crate::CachedKeyHeap
+/// [`IndexableHeap`]: crate::IndexableHeap
+/// [`MaxHeap::by_cached_key`]: crate::MaxHeap::by_cached_key
+/// [`MinHeap::by_cached_key`]: crate::MinHeap::by_cached_key
+pub struct IndexableCachedKeyHeap<T, K, O> {
+    data: IndexableVec<Cached<K, T>>,
+    ord: O,
+}
+
+impl<T, K, O> IndexableCachedKeyHeap<T, K, O> {
+    /// Creates a new empty heap with the specified ordering.
+    pub const fn with_ordering(ord: O) -> Self {
+        Self {
+            data: IndexableVec::new(),
+            ord,
+        }
+    }
+
+    /// Creates a new empty heap with the specified capacity and ordering.
+    ///
+    /// The heap will be able to hold at least `capacity` elements without reallocating.
+    pub fn with_capacity_and_ordering(capacity: usize, ord: O) -> Self {
+        Self {
+            data: IndexableVec::with_capacity(capacity),
+            ord,
+        }
+    }
+
+    /// Returns the number of elements in the heap.
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Returns `true` if the heap is empty.
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Returns `true` if the element with the given index is still in the heap.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(1)
+    pub fn contains(&self, index: Idx<T>) -> bool {
+        self.data.try_index_to_pos(index.cast()).is_some()
+    }
+
+    /// Get a reference to an element by its index, or `None` if the element is no longer in the heap.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(1)
+    pub fn get(&self, index: Idx<T>) -> Option<&T> {
+        let pos = self.data.try_index_to_pos(index.cast())?;
+        Some(&self.data.get(pos).item)
+    }
+
+    /// Returns the cached key of an element by its index, or `None` if the element is no longer in the heap.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(1)
+    pub fn get_key(&self, index: Idx<T>) -> Option<&K> {
+        let pos = self.data.try_index_to_pos(index.cast())?;
+        Some(&self.data.get(pos).key)
+    }
+}
+
+impl<T, K, O: Ordering<K>, F: Fn(&T) -> K> IndexableCachedKeyHeap<T, K, CachedKey<O, F>> {
+    /// Returns the top element, or `None` if the heap is empty.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(1)
+    pub fn peek(&self) -> Option<&T> {
+        self.data.peek().map(|it| &it.item)
+    }
+
+    /// Returns the cached key of the top element, or `None` if the heap is empty.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(1)
+    pub fn peek_key(&self) -> Option<&K> {
+        self.data.peek().map(|it| &it.key)
+    }
+
+    /// Get a mutable reference to an element by its index.
+    ///
+    /// If the element is mutated, its key is computed again,
+    /// and the element is moved into its new position, when the [`GetMut`] is dropped.
+    ///
+    /// # Panics
+    ///
+    /// If the element was removed from the heap.
+    ///
+    /// # Time complexity
+    ///
+    /// If the item is modified then the worst case time complexity is *O*(log(*n*)),
+    /// otherwise it's *O*(1).
+    pub fn by_index_mut(&mut self, index: Idx<T>) -> GetMut<'_, T, K, O, F> {
+        let pos = self.data.index_to_pos(index.cast());
+        GetMut::new(self, pos)
+    }
+
+    /// Get a mutable reference to an element by its index, or `None` if the element is no longer in the heap.
+    ///
+    /// Like [`IndexableCachedKeyHeap::by_index_mut`], the key is computed again when the [`GetMut`] is dropped.
+    /// Unlike it, this method never panics.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use mheap::{IndexableCachedKeyHeap, MaxHeap};
+    ///
+    /// let mut heap = IndexableCachedKeyHeap::with_ordering(MaxHeap::by_cached_key(|v: &Vec<u32>| v.len()));
+    /// heap.push(vec![1, 2]);
+    /// let idx = heap.push(vec![3]);
+    ///
+    /// if let Some(mut entry) = heap.get_mut(idx) {
+    ///     entry.extend([4, 5]);
+    /// }
+    /// assert_eq!(heap.peek_key(), Some(&3));
+    /// assert_eq!(heap.pop(), Some(vec![3, 4, 5]));
+    /// assert!(heap.get_mut(idx).is_none());
+    /// ```
+    ///
+    /// # Time complexity
+    ///
+    /// If the item is modified then the worst case time complexity is *O*(log(*n*)),
+    /// otherwise it's *O*(1).
+    pub fn get_mut(&mut self, index: Idx<T>) -> Option<GetMut<'_, T, K, O, F>> {
+        let pos = self.data.try_index_to_pos(index.cast())?;
+        Some(GetMut::new(self, pos))
+    }
+
+    /// Pushes an element onto the heap, computing its key, and returns an index to it.
+    ///
+    /// # Time complexity
+    ///
+    /// The expected cost of `push` is *O*(1), and the worst case is *O*(log(*n*)).
+    pub fn push(&mut self, item: T) -> Idx<T> {
+        let key = self.ord.key(&item);
+        let pos = self.data.len();
+        let index = self.data.push(Cached { key, item });
+        self.data.sift_up(pos, &self.ord);
+        index.cast()
+    }
+
+    /// Removes the top element from the heap and returns it, or `None` if it is empty.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(log(*n*))
+    pub fn pop(&mut self) -> Option<T> {
+        if self.data.is_empty() {
+            return None;
+        }
+        Some(self.remove_at(0))
+    }
+
+    /// Removes an element by its index and returns it, or `None` if the element is no longer in the heap.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(log(*n*))
+    pub fn remove(&mut self, index: Idx<T>) -> Option<T> {
+        let pos = self.data.try_index_to_pos(index.cast())?;
+        Some(self.remove_at(pos))
+    }
+
+    fn remove_at(&mut self, pos: Position) -> T {
+        let cached = self.data.swap_remove(pos);
+        // In case it was the last element, we don't need to fix its position
+        if pos < self.data.len() {
+            self.data.fixup_sift(pos, &self.ord);
+        }
+        cached.item
+    }
+}
+
+impl<T, K, O: Ordering<K>, F: Fn(&T) -> K> Extend<T> for IndexableCachedKeyHeap<T, K, CachedKey<O, F>> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for item in iter {
+            self.push(item);
+        }
+    }
+}
+
+/// Structure wrapping a mutable reference to an element of an [`IndexableCachedKeyHeap`].
+///
+/// This `struct` is created by [`IndexableCachedKeyHeap::by_index_mut`] and [`IndexableCachedKeyHeap::get_mut`].
+/// If the item was mutated, its key is computed again on drop, and the element is moved into its new position.
+/// If the key function panics there, the item keeps its old key and its position.
+pub struct GetMut<'a, T, K, O: Ordering<K>, F: Fn(&T) -> K> {
+    heap: &'a mut IndexableCachedKeyHeap<T, K, CachedKey<O, F>>,
+    pos: Position,
+    mutated: bool,
+}
+
+impl<'a, T, K, O: Ordering<K>, F: Fn(&T) -> K> GetMut<'a, T, K, O, F> {
+    fn new(heap: &'a mut IndexableCachedKeyHeap<T, K, CachedKey<O, F>>, pos: Position) -> Self {
+        assert!(pos < heap.data.len());
+        Self {
+            heap,
+            pos,
+            mutated: false,
+        }
+    }
+
+    /// Returns the index of the element.
+    pub fn index(&self) -> Idx<T> {
+        self.heap.data.pos_to_index(self.pos).cast()
+    }
+
+    /// Removes the element from the heap and returns it.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(log(*n*))
+    pub fn remove(mut self) -> T {
+        // The element is removed, so neither its key nor its position matter
+        self.mutated = false;
+        self.heap.remove_at(self.pos)
+    }
+}
+
+impl<T, K, O: Ordering<K>, F: Fn(&T) -> K> Drop for GetMut<'_, T, K, O, F> {
+    fn drop(&mut self) {
+        if self.mutated {
+            // Compute the key before touching the heap, like `cached_key_heap::PeekMut` does
+            let key = self.heap.ord.key(&self.heap.data.get(self.pos).item);
+            self.heap.data.get_mut(self.pos).key = key;
+            self.heap.data.fixup_sift(self.pos, &self.heap.ord);
+        }
+    }
+}
+
+impl<T, K, O: Ordering<K>, F: Fn(&T) -> K> Deref for GetMut<'_, T, K, O, F> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.heap.data.get(self.pos).item
+    }
+}
+
+impl<T, K, O: Ordering<K>, F: Fn(&T) -> K> DerefMut for GetMut<'_, T, K, O, F> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.mutated = true;
+        &mut self.heap.data.get_mut(self.pos).item
+    }
+}
@@ -2,11 +2,19 @@
 //! 
 //! See [`IndexableHeap`] for details.
 
-use std::ops::{Deref, DerefMut};
+use alloc::vec::{self, Vec};
+use core::{
+    iter::FusedIterator,
+    mem,
+    ops::{Deref, DerefMut},
+    slice,
+};
 
 use crate::{
-    ConstDefault, ordering::Ordering, Position, RawHeap, raw_heap,
+    ConstDefault, ordering::Ordering, Position, RawHeap,
+    raw_heap::{self, RebuildOnDrop},
     indexable_vec::IndexableVec,
+    shape::{Binary, Shape, Shaped},
 };
 
 pub use crate::indexable_vec::Idx;
@@ -18,6 +26,7 @@ pub use crate::indexable_vec::Idx;
 /// even after the heap has been reordered.
 ///
 /// Use the `O` generic parameter to select [`MaxHeap`] or [`MinHeap`].
+/// The `S` generic parameter selects the tree shape, binary by default. See [`crate::shape`] for details.
 ///
 /// It stores elements in a [`Vec`] like [`VecHeap`] but also tracks their positions in a side map.
 /// On push it returns an opaque handle [`Idx`] to the element.
@@ -53,19 +62,73 @@ pub use crate::indexable_vec::Idx;
 /// [`VecHeap`]: crate::VecHeap
 /// [`MaxHeap`]: crate::MaxHeap
 /// [`MinHeap`]: crate::MinHeap
-pub struct IndexableHeap<T, O> {
-    data: IndexableVec<T>,
+pub struct IndexableHeap<T, O, S = Binary> {
+    data: Shaped<IndexableVec<T>, S>,
     ord: O,
 }
 
 impl<T, O> IndexableHeap<T, O> {
+    /// Creates a new empty heap with the specified ordering.
+    ///
+    /// The heap is binary. Use [`IndexableHeap::with_ordering_and_shape`] for other shapes.
+    pub const fn with_ordering(ord: O) -> Self {
+        Self {
+            data: Shaped::new(IndexableVec::new()),
+            ord,
+        }
+    }
+
+    /// Creates a new empty heap with the specified capacity and ordering.
+    ///
+    /// The heap will be able to hold at least `capacity` elements without reallocating.
+    pub fn with_capacity_and_ordering(capacity: usize, ord: O) -> Self {
+        Self {
+            data: Shaped::new(IndexableVec::with_capacity(capacity)),
+            ord,
+        }
+    }
+
+    /// Creates a heap from a vector with the specified ordering.
+    ///
+    /// Returns the heap together with the indices issued for the elements,
+    /// in the same order as the elements of `vec`.
+    ///
+    /// The heap is built in place with a single bottom-up pass,
+    /// which is cheaper than pushing the elements one by one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mheap::{IndexableHeap, MinHeap};
+    ///
+    /// let (mut heap, indices) = IndexableHeap::from_vec_with_ordering(vec![3, 1, 5], MinHeap::natural());
+    /// assert_eq!(heap.by_index(indices[2]), &5);
+    ///
+    /// *heap.by_index_mut(indices[2]) = 0;
+    /// assert_eq!(heap.pop(), Some(0));
+    /// assert_eq!(heap.pop(), Some(1));
+    /// assert_eq!(heap.pop(), Some(3));
+    /// ```
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*n*)
+    pub fn from_vec_with_ordering(vec: Vec<T>, ord: O) -> (Self, Vec<Idx<T>>)
+    where
+        O: Ordering<T>,
+    {
+        Self::from_vec_in_shape(vec, ord)
+    }
+}
+
+impl<T, O, S> IndexableHeap<T, O, S> {
     /// Creates a new empty heap.
     pub const fn new() -> Self
     where
         O: ConstDefault,
     {
         Self {
-            data: IndexableVec::new(),
+            data: Shaped::new(IndexableVec::new()),
             ord: O::DEFAULT,
         }
     }
@@ -78,27 +141,40 @@ impl<T, O> IndexableHeap<T, O> {
         O: Default,
     {
         Self {
-            data: IndexableVec::with_capacity(capacity),
+            data: Shaped::new(IndexableVec::with_capacity(capacity)),
             ord: O::default(),
         }
     }
 
-    /// Creates a new empty heap with the specified ordering.
-    pub const fn with_ordering(ord: O) -> Self {
+    /// Creates a new empty heap with the specified ordering and tree shape.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mheap::{IndexableHeap, MinHeap, shape::DAry};
+    ///
+    /// let mut heap = IndexableHeap::with_ordering_and_shape(MinHeap::natural(), DAry::<4>);
+    /// let idx = heap.push(5);
+    /// heap.push(3);
+    /// heap.decrease_key(idx, 1);
+    /// assert_eq!(heap.pop(), Some(1));
+    /// ```
+    pub fn with_ordering_and_shape(ord: O, _shape: S) -> Self {
         Self {
-            data: IndexableVec::new(),
+            data: Shaped::new(IndexableVec::new()),
             ord,
         }
     }
 
-    /// Creates a new empty heap with the specified capacity and ordering.
-    ///
-    /// The heap will be able to hold at least `capacity` elements without reallocating.
-    pub fn with_capacity_and_ordering(capacity: usize, ord: O) -> Self {
-        Self {
-            data: IndexableVec::with_capacity(capacity),
-            ord,
-        }
+    fn from_vec_in_shape(vec: Vec<T>, ord: O) -> (Self, Vec<Idx<T>>)
+    where
+        O: Ordering<T>,
+        S: Shape,
+    {
+        let mut data = Shaped::new(IndexableVec::with_capacity(vec.len()));
+        let indices = vec.into_iter().map(|item| data.push(item)).collect();
+        data.rebuild(&ord);
+        (Self { data, ord }, indices)
     }
 
     /// Returns the number of elements in the heap.
@@ -145,9 +221,74 @@ impl<T, O> IndexableHeap<T, O> {
     pub fn is_empty(&self) -> bool {
         self.data.is_empty()
     }
+
+    /// Returns an iterator visiting all elements together with their indices, in arbitrary order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use mheap::{IndexableHeap, MaxHeap};
+    ///
+    /// let mut heap = IndexableHeap::<i32, MaxHeap>::new();
+    /// let idx = heap.push(3);
+    /// heap.push(1);
+    ///
+    /// assert_eq!(heap.iter().map(|(_, x)| x).sum::<i32>(), 4);
+    /// assert!(heap.iter().any(|(i, x)| i == idx && *x == 3));
+    /// ```
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            iter: self.data.entries().iter(),
+        }
+    }
+
+    /// Returns an iterator visiting the indices of all elements, in arbitrary order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use mheap::{IndexableHeap, MaxHeap};
+    ///
+    /// let mut heap = IndexableHeap::<i32, MaxHeap>::new();
+    /// let idx = heap.push(3);
+    /// heap.push(1);
+    ///
+    /// assert_eq!(heap.indices().len(), 2);
+    /// assert!(heap.indices().any(|i| i == idx));
+    /// ```
+    pub fn indices(&self) -> Indices<'_, T> {
+        Indices {
+            iter: self.data.entries().iter(),
+        }
+    }
+
+    /// Clears the heap, returning an iterator over the removed elements and their indices, in arbitrary order.
+    ///
+    /// All indices become invalid, even if the iterator is not consumed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use mheap::{IndexableHeap, MaxHeap};
+    ///
+    /// let mut heap = IndexableHeap::<i32, MaxHeap>::new();
+    /// let idx = heap.push(3);
+    /// heap.push(1);
+    ///
+    /// let mut drained: Vec<_> = heap.drain().map(|(_, x)| x).collect();
+    /// drained.sort();
+    /// assert_eq!(drained, [1, 3]);
+    /// assert!(heap.is_empty());
+    /// assert!(!heap.contains(idx));
+    /// ```
+    pub fn drain(&mut self) -> Drain<'_, T> {
+        Drain {
+            iter: self.data.drain(),
+        }
+    }
 }
 
-impl<T, O: Ordering<T>> IndexableHeap<T, O> {
+impl<T, O: Ordering<T>, S: Shape> IndexableHeap<T, O, S> {
     /// Returns a reference to the top element in the heap, or `None` if it is empty.
     ///
     /// # Examples
@@ -198,7 +339,7 @@ impl<T, O: Ordering<T>> IndexableHeap<T, O> {
     ///
     /// If the item is modified then the worst case time complexity is *O*(log(*n*)),
     /// otherwise it's *O*(1).
-    pub fn peek_mut(&mut self) -> Option<PeekMut<'_, T, O>> {
+    pub fn peek_mut(&mut self) -> Option<PeekMut<'_, T, O, S>> {
         RawHeap::peek_mut(&mut self.data).map(|raw| PeekMut {
             raw,
             ord: &self.ord,
@@ -222,7 +363,7 @@ impl<T, O: Ordering<T>> IndexableHeap<T, O> {
     /// 
     /// # Panics
     /// 
-    /// If the index is invalid, the method might, or might not panic.
+    /// If the element was removed from the heap.
     pub fn by_index(&self, index: Idx<T>) -> &T {
         let pos = self.data.index_to_pos(index);
         self.data.get(pos)
@@ -260,11 +401,279 @@ impl<T, O: Ordering<T>> IndexableHeap<T, O> {
     ///
     /// If the item is modified then the worst case time complexity is *O*(log(*n*)),
     /// otherwise it's *O*(1).
-    pub fn by_index_mut(&mut self, index: Idx<T>) -> GetMut<'_, T, O> {
+    pub fn by_index_mut(&mut self, index: Idx<T>) -> GetMut<'_, T, O, S> {
         let pos = self.data.index_to_pos(index);
         GetMut::new(self, pos)
     }
 
+    /// Returns `true` if the element with the given index is still in the heap.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use mheap::{IndexableHeap, MaxHeap};
+    ///
+    /// let mut heap = IndexableHeap::<i32, MaxHeap>::new();
+    /// let idx = heap.push(3);
+    /// assert!(heap.contains(idx));
+    ///
+    /// heap.pop();
+    /// assert!(!heap.contains(idx));
+    /// ```
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(1)
+    pub fn contains(&self, index: Idx<T>) -> bool {
+        self.data.try_index_to_pos(index).is_some()
+    }
+
+    /// Get a reference to an element by its index, or `None` if the element is no longer in the heap.
+    ///
+    /// Unlike [`IndexableHeap::by_index`], this method never panics.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use mheap::{IndexableHeap, MaxHeap};
+    ///
+    /// let mut heap = IndexableHeap::<i32, MaxHeap>::new();
+    /// let idx = heap.push(3);
+    /// heap.push(1);
+    /// assert_eq!(heap.get(idx), Some(&3));
+    ///
+    /// heap.pop();
+    /// assert_eq!(heap.get(idx), None);
+    /// ```
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(1)
+    pub fn get(&self, index: Idx<T>) -> Option<&T> {
+        let pos = self.data.try_index_to_pos(index)?;
+        Some(self.data.get(pos))
+    }
+
+    /// Get a mutable reference to an element by its index, or `None` if the element is no longer in the heap.
+    ///
+    /// Like [`IndexableHeap::by_index_mut`], the returned wrapper restores the heap invariant when dropped.
+    /// Unlike it, this method never panics.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use mheap::{IndexableHeap, MinHeap};
+    ///
+    /// let mut heap = IndexableHeap::<i32, MinHeap>::new();
+    /// heap.push(5);
+    /// let idx = heap.push(7);
+    ///
+    /// if let Some(mut entry) = heap.get_mut(idx) {
+    ///     *entry = 2;
+    /// }
+    /// assert_eq!(heap.pop(), Some(2));
+    /// assert!(heap.get_mut(idx).is_none());
+    /// ```
+    ///
+    /// # Time complexity
+    ///
+    /// If the item is modified then the worst case time complexity is *O*(log(*n*)),
+    /// otherwise it's *O*(1).
+    pub fn get_mut(&mut self, index: Idx<T>) -> Option<GetMut<'_, T, O, S>> {
+        let pos = self.data.try_index_to_pos(index)?;
+        Some(GetMut::new(self, pos))
+    }
+
+    /// Replaces an element with a value of higher (or equal) priority, moving it towards the top of the heap.
+    ///
+    /// Returns the old value, or `None` if the element is no longer in the heap.
+    ///
+    /// The name follows the classic min-heap terminology, where the key with the highest priority is the smallest.
+    /// So for a [`MinHeap`] the new value must not be greater than the old one,
+    /// and for a [`MaxHeap`] it must not be less.
+    ///
+    /// Unlike [`IndexableHeap::get_mut`], this method only sifts the element up,
+    /// without trying to sift it down.
+    ///
+    /// # Panics
+    ///
+    /// In debug builds, if the new value has lower priority than the old one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use mheap::{IndexableHeap, MinHeap};
+    ///
+    /// let mut heap = IndexableHeap::<i32, MinHeap>::new();
+    /// heap.push(5);
+    /// let idx = heap.push(7);
+    ///
+    /// assert_eq!(heap.decrease_key(idx, 2), Some(7));
+    /// assert_eq!(heap.pop(), Some(2));
+    /// assert_eq!(heap.decrease_key(idx, 1), None);
+    /// ```
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(log(*n*))
+    ///
+    /// [`MaxHeap`]: crate::MaxHeap
+    /// [`MinHeap`]: crate::MinHeap
+    pub fn decrease_key(&mut self, index: Idx<T>, value: T) -> Option<T> {
+        let pos = self.data.try_index_to_pos(index)?;
+        debug_assert!(
+            !self.ord.should_sift_down(&value, self.data.get(pos)),
+            "decrease_key: the new value has lower priority than the old one"
+        );
+        let old = mem::replace(self.data.get_mut(pos), value);
+        self.data.sift_up(pos, &self.ord);
+        Some(old)
+    }
+
+    /// Replaces an element with a value of lower (or equal) priority, moving it towards the bottom of the heap.
+    ///
+    /// Returns the old value, or `None` if the element is no longer in the heap.
+    ///
+    /// This is the counterpart of [`IndexableHeap::decrease_key`].
+    /// So for a [`MinHeap`] the new value must not be less than the old one,
+    /// and for a [`MaxHeap`] it must not be greater.
+    ///
+    /// # Panics
+    ///
+    /// In debug builds, if the new value has higher priority than the old one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use mheap::{IndexableHeap, MinHeap};
+    ///
+    /// let mut heap = IndexableHeap::<i32, MinHeap>::new();
+    /// let idx = heap.push(5);
+    /// heap.push(7);
+    ///
+    /// assert_eq!(heap.increase_key(idx, 10), Some(5));
+    /// assert_eq!(heap.pop(), Some(7));
+    /// assert_eq!(heap.pop(), Some(10));
+    /// ```
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(log(*n*))
+    ///
+    /// [`MaxHeap`]: crate::MaxHeap
+    /// [`MinHeap`]: crate::MinHeap
+    pub fn increase_key(&mut self, index: Idx<T>, value: T) -> Option<T> {
+        let pos = self.data.try_index_to_pos(index)?;
+        debug_assert!(
+            !self.ord.should_sift_up(&value, self.data.get(pos)),
+            "increase_key: the new value has higher priority than the old one"
+        );
+        let old = mem::replace(self.data.get_mut(pos), value);
+        self.data.sift_down(pos, &self.ord);
+        Some(old)
+    }
+
+    /// Replaces an element with the value computed by `f` from the old one.
+    ///
+    /// Returns the old value, or `None` if the element is no longer in the heap.
+    ///
+    /// The old and the new values are compared once, to select the direction to sift the element.
+    /// If they have equal priority, the element stays in place.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use mheap::{IndexableHeap, MaxHeap};
+    ///
+    /// let mut heap = IndexableHeap::<i32, MaxHeap>::new();
+    /// let idx = heap.push(5);
+    /// heap.push(7);
+    ///
+    /// assert_eq!(heap.update(idx, |x| x * 2), Some(5));
+    /// assert_eq!(heap.pop(), Some(10));
+    /// assert_eq!(heap.pop(), Some(7));
+    /// ```
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(log(*n*))
+    pub fn update<F: FnOnce(&T) -> T>(&mut self, index: Idx<T>, f: F) -> Option<T> {
+        let pos = self.data.try_index_to_pos(index)?;
+        let value = f(self.data.get(pos));
+        let up = self.ord.should_sift_up(&value, self.data.get(pos));
+        let down = !up && self.ord.should_sift_down(&value, self.data.get(pos));
+        let old = mem::replace(self.data.get_mut(pos), value);
+        if up {
+            self.data.sift_up(pos, &self.ord);
+        } else if down {
+            self.data.sift_down(pos, &self.ord);
+        }
+        Some(old)
+    }
+
+    /// Removes an element by its index and returns it, or `None` if the element is no longer in the heap.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use mheap::{IndexableHeap, MinHeap};
+    ///
+    /// let mut heap = IndexableHeap::<i32, MinHeap>::new();
+    /// heap.push(16);
+    /// let idx = heap.push(7);
+    /// heap.push(5);
+    ///
+    /// assert_eq!(heap.remove(idx), Some(7));
+    /// assert_eq!(heap.remove(idx), None);
+    ///
+    /// assert_eq!(heap.pop(), Some(5));
+    /// assert_eq!(heap.pop(), Some(16));
+    /// ```
+    ///
+    /// # Time complexity
+    ///
+    /// Worst case is *O*(log(*n*))
+    pub fn remove(&mut self, index: Idx<T>) -> Option<T> {
+        self.get_mut(index).map(GetMut::remove)
+    }
+
+    /// Allows to modify many elements at once, restoring the heap invariant only once at the end.
+    ///
+    /// Inside the closure, the elements are modified through a [`BatchUpdate`].
+    /// The modifications only mark elements as dirty.
+    /// When the closure returns (or panics), the heap is restored,
+    /// either by sifting only the dirty elements, or by rebuilding the whole heap,
+    /// whichever is expected to be cheaper.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mheap::{IndexableHeap, MinHeap};
+    ///
+    /// let mut heap = IndexableHeap::<i32, MinHeap>::new();
+    /// let indices: Vec<_> = (0..10).map(|x| heap.push(x)).collect();
+    ///
+    /// heap.batch_update(|batch| {
+    ///     for idx in &indices {
+    ///         if let Some(x) = batch.get_mut(*idx) {
+    ///             *x = 100 - *x;
+    ///         }
+    ///     }
+    /// });
+    /// assert_eq!(heap.pop(), Some(91));
+    /// ```
+    ///
+    /// # Time complexity
+    ///
+    /// For *k* modified elements, *O*(min(*n*, *k* log(*n*)<sup>2</sup>)).
+    pub fn batch_update<R, F: FnOnce(&mut BatchUpdate<'_, T, O, S>) -> R>(&mut self, f: F) -> R {
+        let mut batch = BatchUpdate {
+            heap: self,
+            dirty: Vec::new(),
+        };
+        f(&mut batch)
+    }
+
     /// Pushes an item onto the heap and returns an index to it.
     ///
     /// The returned index can be used later to access the element even after
@@ -329,8 +738,75 @@ impl<T, O: Ordering<T>> IndexableHeap<T, O> {
     ///
     /// The worst case cost of `pop` on a heap containing *n* elements is *O*(log(*n*)).
     pub fn pop(&mut self) -> Option<T> {
-        let item = self.data.pop()?;
-        Some(self.data.pop_swap(item, &self.ord))
+        self.peek_mut().map(PeekMut::pop)
+    }
+
+    /// Pushes all elements of the iterator onto the heap and returns their indices,
+    /// in the same order as the elements were yielded.
+    ///
+    /// Like [`Extend::extend`], the heap invariant is restored once,
+    /// after all elements were added.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mheap::{IndexableHeap, MinHeap};
+    ///
+    /// let mut heap = IndexableHeap::<i32, MinHeap>::new();
+    /// heap.push(3);
+    /// let indices = heap.extend_with_indices([7, 1, 5]);
+    /// assert_eq!(heap.by_index(indices[0]), &7);
+    ///
+    /// *heap.by_index_mut(indices[0]) = 0;
+    /// assert_eq!(heap.pop(), Some(0));
+    /// assert_eq!(heap.pop(), Some(1));
+    /// ```
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(min(*k* log(*n*), *n* + *k*)), where *k* is the number of new elements.
+    pub fn extend_with_indices<I: IntoIterator<Item = T>>(&mut self, iter: I) -> Vec<Idx<T>> {
+        let guard = RebuildOnDrop::new(&mut self.data, &self.ord);
+        iter.into_iter().map(|item| guard.data.push(item)).collect()
+    }
+
+    /// Moves all the elements of `other` into `self`, leaving `other` empty.
+    ///
+    /// The elements get new indices in `self`, and all indices of `other` become invalid.
+    /// Returns the pairs `(old, new)` that map the indices of `other` to the new ones, in arbitrary order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::collections::HashMap;
+    /// use mheap::{IndexableHeap, MaxHeap};
+    ///
+    /// let mut a = IndexableHeap::<i32, MaxHeap>::new();
+    /// a.push(1);
+    /// a.push(2);
+    ///
+    /// let mut b = IndexableHeap::<i32, MaxHeap>::new();
+    /// let idx = b.push(4);
+    /// b.push(5);
+    ///
+    /// let remap: HashMap<_, _> = a.append(&mut b).into_iter().collect();
+    /// assert_eq!(a.len(), 4);
+    /// assert!(b.is_empty());
+    /// assert_eq!(a.get(remap[&idx]), Some(&4));
+    /// ```
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(min(*k* log(*n*), *n* + *k*)), where *k* is the number of elements in `other`.
+    pub fn append(&mut self, other: &mut Self) -> Vec<(Idx<T>, Idx<T>)> {
+        self.reserve(other.len());
+
+        let guard = RebuildOnDrop::new(&mut self.data, &self.ord);
+        other
+            .data
+            .drain()
+            .map(|(item, old)| (old, guard.data.push(item)))
+            .collect()
     }
 
     /// Reserves capacity for at least `additional` elements more than the
@@ -421,38 +897,102 @@ impl<T, O: Ordering<T>> IndexableHeap<T, O> {
     }
 }
 
+impl<T, O: Default, S> Default for IndexableHeap<T, O, S> {
+    /// Creates an empty heap.
+    fn default() -> Self {
+        Self {
+            data: Shaped::new(IndexableVec::new()),
+            ord: O::default(),
+        }
+    }
+}
+
+impl<T, O: Ordering<T> + Default, S: Shape> From<Vec<T>> for IndexableHeap<T, O, S> {
+    /// Converts a `Vec<T>` into a heap in *O*(*n*) time.
+    ///
+    /// The issued indices are discarded.
+    /// Use [`IndexableHeap::from_vec_with_ordering`] to obtain them.
+    fn from(vec: Vec<T>) -> Self {
+        Self::from_vec_in_shape(vec, O::default()).0
+    }
+}
+
+impl<T, O: Ordering<T> + Default, S: Shape, const N: usize> From<[T; N]> for IndexableHeap<T, O, S> {
+    /// Converts a `[T; N]` into a heap in *O*(*n*) time.
+    ///
+    /// The issued indices are discarded.
+    /// Use [`IndexableHeap::from_vec_with_ordering`] to obtain them.
+    fn from(arr: [T; N]) -> Self {
+        Self::from(Vec::from(arr))
+    }
+}
+
+impl<T, O: Ordering<T> + Default, S: Shape> FromIterator<T> for IndexableHeap<T, O, S> {
+    /// Collects the elements into a heap.
+    ///
+    /// The issued indices are discarded.
+    /// Use [`IndexableHeap::from_vec_with_ordering`] to obtain them.
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Self::from(iter.into_iter().collect::<Vec<_>>())
+    }
+}
+
+impl<T, O: Ordering<T>, S: Shape> Extend<T> for IndexableHeap<T, O, S> {
+    /// Pushes all elements of the iterator onto the heap.
+    ///
+    /// The heap invariant is restored once, after all elements were added.
+    /// The issued indices are discarded.
+    /// Use [`IndexableHeap::extend_with_indices`] to obtain them.
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        let iter = iter.into_iter();
+        self.reserve(iter.size_hint().0);
+
+        let guard = RebuildOnDrop::new(&mut self.data, &self.ord);
+        for item in iter {
+            guard.data.push(item);
+        }
+    }
+}
+
+impl<'a, T: Copy + 'a, O: Ordering<T>, S: Shape> Extend<&'a T> for IndexableHeap<T, O, S> {
+    /// Pushes copies of all elements of the iterator onto the heap.
+    fn extend<I: IntoIterator<Item = &'a T>>(&mut self, iter: I) {
+        self.extend(iter.into_iter().copied());
+    }
+}
+
 /// Structure wrapping a mutable reference to the top item on an [`IndexableHeap`].
 ///
 /// This `struct` is created by the [`peek_mut`] method on [`IndexableHeap`]. See
 /// its documentation for more.
 ///
 /// [`peek_mut`]: IndexableHeap::peek_mut
-pub struct PeekMut<'a, T, O: Ordering<T>> {
-    raw: raw_heap::PeekMut<'a, IndexableVec<T>>,
+pub struct PeekMut<'a, T, O: Ordering<T>, S: Shape = Binary> {
+    raw: raw_heap::PeekMut<'a, Shaped<IndexableVec<T>, S>>,
     ord: &'a O,
 }
 
-impl<'a, T, O: Ordering<T>> Drop for PeekMut<'a, T, O> {
+impl<'a, T, O: Ordering<T>, S: Shape> Drop for PeekMut<'a, T, O, S> {
     fn drop(&mut self) {
         self.restore();
     }
 }
 
-impl<'a, T, O: Ordering<T>> Deref for PeekMut<'a, T, O> {
+impl<'a, T, O: Ordering<T>, S: Shape> Deref for PeekMut<'a, T, O, S> {
     type Target = T;
 
     fn deref(&self) -> &Self::Target {
-        self.raw.as_ref()
+        self.raw.item()
     }
 }
 
-impl<'a, T, O: Ordering<T>> DerefMut for PeekMut<'a, T, O> {
+impl<'a, T, O: Ordering<T>, S: Shape> DerefMut for PeekMut<'a, T, O, S> {
     fn deref_mut(&mut self) -> &mut Self::Target {
-        self.raw.as_mut()
+        self.raw.item_mut()
     }
 }
 
-impl<'a, T, O: Ordering<T>> PeekMut<'a, T, O> {
+impl<'a, T, O: Ordering<T>, S: Shape> PeekMut<'a, T, O, S> {
     fn restore(&mut self) {
         self.raw.restore(self.ord);
     }
@@ -516,9 +1056,229 @@ impl<'a, T, O: Ordering<T>> PeekMut<'a, T, O> {
         // We don't care if the element was mutated, as we will remove in the next line
         self.raw.ignore_mutation();
 
-        let heap = self.raw.heap_mut();
-        let item = heap.pop().unwrap();
-        heap.pop_swap(item, self.ord)
+        let pos = self.raw.pos();
+        remove_at(self.raw.heap_mut(), pos, self.ord)
+    }
+}
+
+impl<T, O, S> IntoIterator for IndexableHeap<T, O, S> {
+    type Item = (Idx<T>, T);
+    type IntoIter = IntoIter<T>;
+
+    /// Creates a consuming iterator, that moves each element together with its index
+    /// out of the heap in arbitrary order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use mheap::{IndexableHeap, MaxHeap};
+    ///
+    /// let mut heap = IndexableHeap::<i32, MaxHeap>::new();
+    /// let idx = heap.push(3);
+    /// heap.push(1);
+    ///
+    /// let entries: Vec<_> = heap.into_iter().collect();
+    /// assert_eq!(entries.len(), 2);
+    /// assert!(entries.contains(&(idx, 3)));
+    /// ```
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter {
+            iter: self.data.into_inner().into_entries().into_iter(),
+        }
+    }
+}
+
+impl<'a, T, O, S> IntoIterator for &'a IndexableHeap<T, O, S> {
+    type Item = (Idx<T>, &'a T);
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// An iterator over the elements of an [`IndexableHeap`] and their indices, in arbitrary order.
+///
+/// This `struct` is created by [`IndexableHeap::iter`]. See its documentation for more.
+pub struct Iter<'a, T> {
+    iter: slice::Iter<'a, (T, Idx<T>)>,
+}
+
+impl<T> Clone for Iter<'_, T> {
+    fn clone(&self) -> Self {
+        Self {
+            iter: self.iter.clone(),
+        }
+    }
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = (Idx<T>, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().map(|(item, index)| (*index, item))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<T> DoubleEndedIterator for Iter<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.iter.next_back().map(|(item, index)| (*index, item))
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+
+impl<T> FusedIterator for Iter<'_, T> {}
+
+/// An iterator over the indices of the elements of an [`IndexableHeap`], in arbitrary order.
+///
+/// This `struct` is created by [`IndexableHeap::indices`]. See its documentation for more.
+pub struct Indices<'a, T> {
+    iter: slice::Iter<'a, (T, Idx<T>)>,
+}
+
+impl<T> Clone for Indices<'_, T> {
+    fn clone(&self) -> Self {
+        Self {
+            iter: self.iter.clone(),
+        }
+    }
+}
+
+impl<T> Iterator for Indices<'_, T> {
+    type Item = Idx<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().map(|(_, index)| *index)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<T> DoubleEndedIterator for Indices<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.iter.next_back().map(|(_, index)| *index)
+    }
+}
+
+impl<T> ExactSizeIterator for Indices<'_, T> {}
+
+impl<T> FusedIterator for Indices<'_, T> {}
+
+/// An owning iterator over the elements of an [`IndexableHeap`] and their indices, in arbitrary order.
+///
+/// This `struct` is created by [`IndexableHeap::into_iter`]
+/// (provided by the [`IntoIterator`] trait). See its documentation for more.
+pub struct IntoIter<T> {
+    iter: vec::IntoIter<(T, Idx<T>)>,
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = (Idx<T>, T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().map(|(item, index)| (index, item))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<T> DoubleEndedIterator for IntoIter<T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.iter.next_back().map(|(item, index)| (index, item))
+    }
+}
+
+impl<T> ExactSizeIterator for IntoIter<T> {}
+
+impl<T> FusedIterator for IntoIter<T> {}
+
+/// A draining iterator over the elements of an [`IndexableHeap`] and their indices, in arbitrary order.
+///
+/// This `struct` is created by [`IndexableHeap::drain`]. See its documentation for more.
+pub struct Drain<'a, T> {
+    iter: vec::Drain<'a, (T, Idx<T>)>,
+}
+
+impl<T> Iterator for Drain<'_, T> {
+    type Item = (Idx<T>, T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().map(|(item, index)| (index, item))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<T> DoubleEndedIterator for Drain<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.iter.next_back().map(|(item, index)| (index, item))
+    }
+}
+
+impl<T> ExactSizeIterator for Drain<'_, T> {}
+
+impl<T> FusedIterator for Drain<'_, T> {}
+
+/// A batch of modifications of an [`IndexableHeap`], that restores the heap invariant when dropped.
+///
+/// This `struct` is created by the [`batch_update`] method on [`IndexableHeap`]. See
+/// its documentation for more.
+///
+/// [`batch_update`]: IndexableHeap::batch_update
+pub struct BatchUpdate<'a, T, O: Ordering<T>, S: Shape = Binary> {
+    heap: &'a mut IndexableHeap<T, O, S>,
+    dirty: Vec<Position>,
+}
+
+impl<T, O: Ordering<T>, S: Shape> Drop for BatchUpdate<'_, T, O, S> {
+    fn drop(&mut self) {
+        self.heap.data.rebuild_dirty(&mut self.dirty, &self.heap.ord);
+    }
+}
+
+impl<T, O: Ordering<T>, S: Shape> BatchUpdate<'_, T, O, S> {
+    /// Returns the number of elements in the heap.
+    pub fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    /// Returns `true` if the heap is empty.
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+
+    /// Returns `true` if the element with the given index is still in the heap.
+    ///
+    /// See [`IndexableHeap::contains`].
+    pub fn contains(&self, index: Idx<T>) -> bool {
+        self.heap.contains(index)
+    }
+
+    /// Get a reference to an element by its index, or `None` if the element is no longer in the heap.
+    ///
+    /// See [`IndexableHeap::get`].
+    pub fn get(&self, index: Idx<T>) -> Option<&T> {
+        self.heap.get(index)
+    }
+
+    /// Get a mutable reference to an element by its index, or `None` if the element is no longer in the heap.
+    ///
+    /// The element is marked as dirty, and is put into its new position at the end of the batch.
+    pub fn get_mut(&mut self, index: Idx<T>) -> Option<&mut T> {
+        let pos = self.heap.data.try_index_to_pos(index)?;
+        self.dirty.push(pos);
+        Some(self.heap.data.get_mut(pos))
     }
 }
 
@@ -528,13 +1288,13 @@ impl<'a, T, O: Ordering<T>> PeekMut<'a, T, O> {
 /// its documentation for more.
 ///
 /// [`by_index_mut`]: IndexableHeap::by_index_mut
-pub struct GetMut<'a, T, O: Ordering<T>> {
-    heap: &'a mut IndexableHeap<T, O>,
+pub struct GetMut<'a, T, O: Ordering<T>, S: Shape = Binary> {
+    heap: &'a mut IndexableHeap<T, O, S>,
     pos: Position,
     sift: bool,
 }
 
-impl<'a, T, O: Ordering<T>> Deref for GetMut<'a, T, O> {
+impl<'a, T, O: Ordering<T>, S: Shape> Deref for GetMut<'a, T, O, S> {
     type Target = T;
 
     fn deref(&self) -> &Self::Target {
@@ -542,20 +1302,20 @@ impl<'a, T, O: Ordering<T>> Deref for GetMut<'a, T, O> {
     }
 }
 
-impl<'a, T, O: Ordering<T>> DerefMut for GetMut<'a, T, O> {
+impl<'a, T, O: Ordering<T>, S: Shape> DerefMut for GetMut<'a, T, O, S> {
     fn deref_mut(&mut self) -> &mut Self::Target {
         self.as_mut()
     }
 }
 
-impl<'a, T, O: Ordering<T>> Drop for GetMut<'a, T, O> {
+impl<'a, T, O: Ordering<T>, S: Shape> Drop for GetMut<'a, T, O, S> {
     fn drop(&mut self) {
         self.restore();
     }
 }
 
-impl<'a, T, O: Ordering<T>> GetMut<'a, T, O> {
-    fn new(heap: &'a mut IndexableHeap<T, O>, pos: Position) -> Self {
+impl<'a, T, O: Ordering<T>, S: Shape> GetMut<'a, T, O, S> {
+    fn new(heap: &'a mut IndexableHeap<T, O, S>, pos: Position) -> Self {
         assert!(pos < heap.data.len());
         Self {
             heap,
@@ -568,7 +1328,7 @@ impl<'a, T, O: Ordering<T>> GetMut<'a, T, O> {
         if self.pos >= self.heap.data.len() {
             // SAFETY: checked invariant in the new call
             unsafe {
-                std::hint::unreachable_unchecked();
+                core::hint::unreachable_unchecked();
             }
         }
         self.pos
@@ -604,7 +1364,8 @@ impl<'a, T, O: Ordering<T>> GetMut<'a, T, O> {
     fn as_mut(&mut self) -> &mut T {
         self.sift = true;
 
-        self.heap.data.get_mut(self.pos())
+        let pos = self.pos();
+        self.heap.data.get_mut(pos)
     }
 
     fn restore(&mut self) -> bool {
@@ -649,11 +1410,19 @@ impl<'a, T, O: Ordering<T>> GetMut<'a, T, O> {
         self.sift = false;
 
         let pos = self.pos();
-        let item = self.heap.data.swap_remove(pos);
-        // In case it was the last element, we don't need to fix its position
-        if pos < self.heap.data.len() {
-            self.heap.data.fixup_sift_to_bottom(pos, &self.heap.ord);
-        }
-        item
+        remove_at(&mut self.heap.data, pos, &self.heap.ord)
+    }
+}
+
+/// Removes the element at `pos` together with its index, and restores the heap invariant.
+///
+/// We cannot use [`RawHeap::pop_swap`] here, as it would swap only the elements,
+/// leaving the index of the removed element pointing to the moved one.
+fn remove_at<T, S: Shape>(data: &mut Shaped<IndexableVec<T>, S>, pos: Position, ord: &impl Ordering<T>) -> T {
+    let item = data.swap_remove(pos);
+    // In case it was the last element, we don't need to fix its position
+    if pos < data.len() {
+        data.fixup_sift_to_bottom(pos, ord);
     }
+    item
 }
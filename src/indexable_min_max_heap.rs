@@ -0,0 +1,317 @@
+//! A double-ended priority queue, that allows access to elements by index.
+//!
+//! See [`IndexableMinMaxHeap`] for details.
+
+use alloc::vec::Vec;
+use core::{cmp, mem};
+
+use crate::{
+    Position,
+    indexable_vec::{Idx, IndexableVec},
+    min_max,
+    ordering::{ByCmp, ByKey, Cmp, Natural},
+};
+
+/// A double-ended priority queue, that allows access to elements by index.
+///
+/// It is a [`MinMaxHeap`], that issues an opaque [`Idx`] on push, like [`IndexableHeap`] does.
+/// The index can be used later to access, update or remove the element.
+///
+/// # Examples
+///
+/// ```
+/// use mheap::IndexableMinMaxHeap;
+///
+/// let mut heap = IndexableMinMaxHeap::new();
+/// heap.push(3);
+/// let idx = heap.push(7);
+/// heap.push(5);
+///
+/// assert_eq!(heap.peek_max(), Some(&7));
+/// heap.update(idx, |_| 1);
+/// assert_eq!(heap.peek_min(), Some(&1));
+/// assert_eq!(heap.pop_max(), Some(5));
+/// ```
+///
+/// # Time complexity
+///
+/// | Operation               | Time complexity |
+/// |-------------------------|-----------------|
+/// | `push`                  | *O*(1)~         |
+/// | `pop_min`, `pop_max`    | *O*(log(*n*))   |
+/// | `peek_min`, `peek_max`  | *O*(1)          |
+/// | `update`, `remove`      | *O*(log(*n*))   |
+///
+/// The `push` operation has expected *O*(1) complexity.
+///
+/// [`MinMaxHeap`]: crate::MinMaxHeap
+/// [`IndexableHeap`]: crate::IndexableHeap
+pub struct IndexableMinMaxHeap<T, C = Natural> {
+    data: IndexableVec<T>,
+    cmp: C,
+}
+
+impl<T> IndexableMinMaxHeap<T> {
+    /// Creates a new empty heap, that uses the default `Ord` implementation for comparison.
+    pub const fn new() -> Self {
+        Self::with_cmp(Natural)
+    }
+
+    /// Creates a new empty heap with the specified capacity.
+    ///
+    /// The heap will be able to hold at least `capacity` elements without reallocating.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            data: IndexableVec::with_capacity(capacity),
+            cmp: Natural,
+        }
+    }
+}
+
+impl<T, F: Fn(&T, &T) -> cmp::Ordering> IndexableMinMaxHeap<T, ByCmp<F>> {
+    /// Creates a new empty heap with a custom comparison function.
+    ///
+    /// See [`MinMaxHeap::by`] for details.
+    ///
+    /// [`MinMaxHeap::by`]: crate::MinMaxHeap::by
+    pub fn by(cmp: F) -> Self {
+        Self::with_cmp(ByCmp(cmp))
+    }
+}
+
+impl<T, F> IndexableMinMaxHeap<T, ByKey<F>> {
+    /// Creates a new empty heap, that compares elements by a key extraction function.
+    ///
+    /// See [`MinMaxHeap::by_key`] for details.
+    ///
+    /// [`MinMaxHeap::by_key`]: crate::MinMaxHeap::by_key
+    pub fn by_key<K: Ord>(key: F) -> Self
+    where
+        F: Fn(&T) -> K,
+    {
+        Self::with_cmp(ByKey(key))
+    }
+}
+
+impl<T, C> IndexableMinMaxHeap<T, C> {
+    /// Creates a new empty heap, that compares elements by any [`Cmp`] from [`ordering`].
+    ///
+    /// See [`MinMaxHeap::with_cmp`] for details.
+    ///
+    /// [`ordering`]: crate::ordering
+    /// [`MinMaxHeap::with_cmp`]: crate::MinMaxHeap::with_cmp
+    pub const fn with_cmp(cmp: C) -> Self {
+        Self {
+            data: IndexableVec::new(),
+            cmp,
+        }
+    }
+
+    /// Creates a new empty heap with the specified capacity and comparison.
+    ///
+    /// The heap will be able to hold at least `capacity` elements without reallocating.
+    pub fn with_capacity_and_cmp(capacity: usize, cmp: C) -> Self {
+        Self {
+            data: IndexableVec::with_capacity(capacity),
+            cmp,
+        }
+    }
+
+    /// Returns the number of elements in the heap.
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Returns `true` if the heap is empty.
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Returns `true` if the element with the given index is still in the heap.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(1)
+    pub fn contains(&self, index: Idx<T>) -> bool {
+        self.data.try_index_to_pos(index).is_some()
+    }
+
+    /// Get a reference to an element by its index, or `None` if the element is no longer in the heap.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(1)
+    pub fn get(&self, index: Idx<T>) -> Option<&T> {
+        let pos = self.data.try_index_to_pos(index)?;
+        Some(self.data.get(pos))
+    }
+}
+
+impl<T, C: Cmp<T>> IndexableMinMaxHeap<T, C> {
+    /// Creates a heap from a vector in *O*(*n*) time, with the specified comparison.
+    ///
+    /// Returns the indices of the elements, in the order of the vector.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mheap::{IndexableMinMaxHeap, ordering::Natural};
+    ///
+    /// let (heap, indices) = IndexableMinMaxHeap::from_vec_with_cmp(vec![3, 1, 2], Natural.reverse());
+    /// assert_eq!(heap.peek_min(), Some(&3));
+    /// assert_eq!(heap.get(indices[1]), Some(&1));
+    /// ```
+    pub fn from_vec_with_cmp(vec: Vec<T>, cmp: C) -> (Self, Vec<Idx<T>>) {
+        let mut data = IndexableVec::with_capacity(vec.len());
+        let indices = vec.into_iter().map(|item| data.push(item)).collect();
+        min_max::rebuild(&mut data, &cmp);
+        (Self { data, cmp }, indices)
+    }
+
+    /// Returns the smallest element, or `None` if the heap is empty.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(1)
+    pub fn peek_min(&self) -> Option<&T> {
+        if self.data.is_empty() {
+            return None;
+        }
+        Some(self.data.get(0))
+    }
+
+    /// Returns the largest element, or `None` if the heap is empty.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(1)
+    pub fn peek_max(&self) -> Option<&T> {
+        let pos = min_max::max_pos(&self.data, &self.cmp)?;
+        Some(self.data.get(pos))
+    }
+
+    /// Pushes an item onto the heap and returns an index to it.
+    ///
+    /// # Time complexity
+    ///
+    /// The expected cost of `push` is *O*(1), and the worst case is *O*(log(*n*)).
+    pub fn push(&mut self, item: T) -> Idx<T> {
+        let pos = self.data.len();
+        let index = self.data.push(item);
+        min_max::fixup(&mut self.data, &self.cmp, pos);
+        index
+    }
+
+    /// Removes the smallest element from the heap and returns it, or `None` if it is empty.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(log(*n*))
+    pub fn pop_min(&mut self) -> Option<T> {
+        if self.data.is_empty() {
+            return None;
+        }
+        Some(self.remove_at(0))
+    }
+
+    /// Removes the largest element from the heap and returns it, or `None` if it is empty.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(log(*n*))
+    pub fn pop_max(&mut self) -> Option<T> {
+        let pos = min_max::max_pos(&self.data, &self.cmp)?;
+        Some(self.remove_at(pos))
+    }
+
+    /// Removes an element by its index and returns it, or `None` if the element is no longer in the heap.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mheap::IndexableMinMaxHeap;
+    ///
+    /// let mut heap = IndexableMinMaxHeap::new();
+    /// heap.push(1);
+    /// let idx = heap.push(5);
+    /// heap.push(3);
+    ///
+    /// assert_eq!(heap.remove(idx), Some(5));
+    /// assert_eq!(heap.remove(idx), None);
+    /// assert_eq!(heap.peek_max(), Some(&3));
+    /// ```
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(log(*n*))
+    pub fn remove(&mut self, index: Idx<T>) -> Option<T> {
+        let pos = self.data.try_index_to_pos(index)?;
+        Some(self.remove_at(pos))
+    }
+
+    /// Replaces an element with the value computed by `f` from the old one.
+    ///
+    /// Returns the old value, or `None` if the element is no longer in the heap.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(log(*n*))
+    pub fn update<F: FnOnce(&T) -> T>(&mut self, index: Idx<T>, f: F) -> Option<T> {
+        let pos = self.data.try_index_to_pos(index)?;
+        let value = f(self.data.get(pos));
+        let old = mem::replace(self.data.get_mut(pos), value);
+        min_max::fixup(&mut self.data, &self.cmp, pos);
+        Some(old)
+    }
+
+    fn remove_at(&mut self, pos: Position) -> T {
+        let item = self.data.swap_remove(pos);
+        // In case it was the last element, we don't need to fix its position
+        if pos < self.data.len() {
+            min_max::fixup(&mut self.data, &self.cmp, pos);
+        }
+        item
+    }
+}
+
+impl<T, C: Default> Default for IndexableMinMaxHeap<T, C> {
+    /// Creates an empty heap.
+    fn default() -> Self {
+        Self::with_cmp(C::default())
+    }
+}
+
+impl<T, C: Cmp<T> + Default> From<Vec<T>> for IndexableMinMaxHeap<T, C> {
+    /// Creates a heap from a vector in *O*(*n*) time.
+    ///
+    /// The issued indices are discarded.
+    /// Use [`IndexableMinMaxHeap::from_vec_with_cmp`] to obtain them.
+    fn from(vec: Vec<T>) -> Self {
+        Self::from_vec_with_cmp(vec, C::default()).0
+    }
+}
+
+impl<T, C: Cmp<T> + Default, const N: usize> From<[T; N]> for IndexableMinMaxHeap<T, C> {
+    fn from(arr: [T; N]) -> Self {
+        Self::from(Vec::from(arr))
+    }
+}
+
+impl<T, C: Cmp<T> + Default> FromIterator<T> for IndexableMinMaxHeap<T, C> {
+    /// Collects the elements into a heap.
+    ///
+    /// The issued indices are discarded.
+    /// Use [`IndexableMinMaxHeap::from_vec_with_cmp`] to obtain them.
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Self::from(iter.into_iter().collect::<Vec<_>>())
+    }
+}
+
+impl<T, C: Cmp<T>> Extend<T> for IndexableMinMaxHeap<T, C> {
+    /// Pushes all elements of the iterator onto the heap, discarding the issued indices.
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for item in iter {
+            self.push(item);
+        }
+    }
+}
@@ -0,0 +1,509 @@
+//! A [`SoaHeap`], that allows access to elements by index.
+//!
+//! See [`IndexableSoaHeap`] for details.
+//!
+//! [`SoaHeap`]: crate::SoaHeap
+
+use alloc::vec::Vec;
+use core::mem;
+
+use crate::{
+    ConstDefault, Position, RawHeap,
+    indexable_vec::Idx,
+    ordering::Ordering,
+    shape::{Binary, Shape, Shaped},
+    soa_heap::{self, Iter},
+    soa_vec::SoaVec,
+    storage::Storage,
+    tree,
+};
+
+/// A [`SoaHeap`], that allows access to elements by index.
+///
+/// Like [`IndexableHeap`], it issues an opaque [`Idx`] on push.
+/// The index can be used later to access the value, change the key, or remove the element.
+///
+/// The values stay in place, so they can be modified by index without reordering the heap.
+///
+/// The `S` generic parameter selects the tree shape, binary by default.
+/// See [`crate::shape`] for details.
+///
+/// # Examples
+///
+/// ```
+/// use mheap::{IndexableSoaHeap, MinHeap};
+///
+/// let mut heap = IndexableSoaHeap::<u32, String, MinHeap>::new();
+/// heap.push(5, "five".to_string());
+/// let idx = heap.push(7, "seven".to_string());
+///
+/// heap.value_mut(idx).unwrap().push('!');
+/// assert_eq!(heap.update_key(idx, 1), Some(7));
+/// assert_eq!(heap.pop(), Some((1, "seven!".to_string())));
+/// assert!(!heap.contains(idx));
+/// ```
+///
+/// # Time complexity
+///
+/// | Operation                 | Time complexity |
+/// |---------------------------|-----------------|
+/// | `push`                    | *O*(1)~         |
+/// | `pop`                     | *O*(log(*n*))   |
+/// | `peek`                    | *O*(1)          |
+/// | `get`, `value_mut`        | *O*(1)          |
+/// | `update_key`, `remove`    | *O*(log(*n*))   |
+///
+/// The `push` operation has expected *O*(1) complexity.
+///
+/// [`SoaHeap`]: crate::SoaHeap
+/// [`IndexableHeap`]: crate::IndexableHeap
+pub struct IndexableSoaHeap<K, V, O, S = Binary> {
+    data: Shaped<SoaVec<K, V, true>, S>,
+    ord: O,
+}
+
+impl<K, V, O> IndexableSoaHeap<K, V, O> {
+    /// Creates a new empty heap with the specified ordering of the keys.
+    ///
+    /// The heap is binary. Use [`IndexableSoaHeap::with_ordering_and_shape`] for other shapes.
+    pub const fn with_ordering(ord: O) -> Self {
+        Self {
+            data: Shaped::new(SoaVec::new()),
+            ord,
+        }
+    }
+
+    /// Creates a new empty heap with the specified capacity and ordering of the keys.
+    ///
+    /// The heap will be able to hold at least `capacity` elements without reallocating.
+    pub fn with_capacity_and_ordering(capacity: usize, ord: O) -> Self {
+        Self {
+            data: Shaped::new(SoaVec::with_capacity(capacity)),
+            ord,
+        }
+    }
+
+    /// Creates a heap from a vector of `(key, value)` pairs with the specified ordering of the keys.
+    ///
+    /// Returns the indices of the elements, in the order of the vector.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mheap::{IndexableSoaHeap, MaxHeap};
+    ///
+    /// let (heap, indices) = IndexableSoaHeap::from_vec_with_ordering(vec![(1, 'a'), (3, 'c')], MaxHeap::natural());
+    /// assert_eq!(heap.peek(), Some((&3, &'c')));
+    /// assert_eq!(heap.get(indices[0]), Some((&1, &'a')));
+    /// ```
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*n*)
+    pub fn from_vec_with_ordering(vec: Vec<(K, V)>, ord: O) -> (Self, Vec<Idx<V>>)
+    where
+        O: Ordering<K>,
+    {
+        Self::from_vec_in_shape(vec, ord)
+    }
+}
+
+impl<K, V, O, S> IndexableSoaHeap<K, V, O, S> {
+    /// Creates a new empty heap
+    pub const fn new() -> Self
+    where
+        O: ConstDefault,
+    {
+        Self {
+            data: Shaped::new(SoaVec::new()),
+            ord: O::DEFAULT,
+        }
+    }
+
+    /// Creates a new empty heap with the specified capacity
+    ///
+    /// The heap will be able to hold at least `capacity` elements without reallocating.
+    pub fn with_capacity(capacity: usize) -> Self
+    where
+        O: Default,
+    {
+        Self {
+            data: Shaped::new(SoaVec::with_capacity(capacity)),
+            ord: O::default(),
+        }
+    }
+
+    /// Creates a new empty heap with the specified ordering of the keys and tree shape.
+    pub fn with_ordering_and_shape(ord: O, _shape: S) -> Self {
+        Self {
+            data: Shaped::new(SoaVec::new()),
+            ord,
+        }
+    }
+
+    fn from_vec_in_shape(vec: Vec<(K, V)>, ord: O) -> (Self, Vec<Idx<V>>)
+    where
+        O: Ordering<K>,
+        S: Shape,
+    {
+        let mut data = Shaped::new(SoaVec::with_capacity(vec.len()));
+        let indices = vec.into_iter().map(|(key, value)| data.push(key, value)).collect();
+        data.rebuild(&ord);
+        (Self { data, ord }, indices)
+    }
+
+    /// Returns the number of elements in the heap.
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Returns `true` if the heap is empty.
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Returns the number of elements the heap can hold without reallocating.
+    pub fn capacity(&self) -> usize {
+        self.data.capacity()
+    }
+
+    /// Removes all elements from the heap, invalidating their indices.
+    pub fn clear(&mut self) {
+        self.data.clear();
+    }
+
+    /// Returns an iterator visiting all `(key, value)` pairs, in arbitrary order.
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter::new(&self.data)
+    }
+
+    /// Consumes the heap and returns the pairs in arbitrary order.
+    pub fn into_vec(self) -> Vec<(K, V)> {
+        self.data.into_inner().into_vec()
+    }
+
+    /// Returns `true` if the element with the given index is still in the heap.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(1)
+    pub fn contains(&self, index: Idx<V>) -> bool {
+        self.data.try_index_to_pos(index).is_some()
+    }
+
+    /// Returns the pair by its index, or `None` if the element is no longer in the heap.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(1)
+    pub fn get(&self, index: Idx<V>) -> Option<(&K, &V)> {
+        let pos = self.data.try_index_to_pos(index)?;
+        Some(self.data.entry(pos))
+    }
+
+    /// Returns a mutable reference to a value by its index, or `None` if the element is no longer in the heap.
+    ///
+    /// Only the key is compared, so the heap is not reordered.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(1)
+    pub fn value_mut(&mut self, index: Idx<V>) -> Option<&mut V> {
+        let pos = self.data.try_index_to_pos(index)?;
+        Some(self.data.entry_mut(pos).1)
+    }
+}
+
+impl<K, V, O: Ordering<K>, S: Shape> IndexableSoaHeap<K, V, O, S> {
+    /// Returns the top pair, or `None` if the heap is empty.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(1)
+    pub fn peek(&self) -> Option<(&K, &V)> {
+        let pos = tree::root(&self.data)?;
+        Some(self.data.entry(pos))
+    }
+
+    /// Returns the top pair with a mutable value, or `None` if the heap is empty.
+    ///
+    /// The key cannot be modified, so the heap never needs to be reordered.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(1)
+    pub fn peek_mut(&mut self) -> Option<(&K, &mut V)> {
+        let pos = tree::root(&self.data)?;
+        Some(self.data.entry_mut(pos))
+    }
+
+    /// Pushes a `(key, value)` pair onto the heap and returns an index to it.
+    ///
+    /// # Time complexity
+    ///
+    /// The expected cost of `push` is *O*(1), and the worst case is *O*(log(*n*)).
+    pub fn push(&mut self, key: K, value: V) -> Idx<V> {
+        let pos = self.data.len();
+        let index = self.data.push(key, value);
+        self.data.sift_up(pos, &self.ord);
+        index
+    }
+
+    /// Removes the top pair from the heap and returns it, or `None` if it is empty.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(log(*n*))
+    pub fn pop(&mut self) -> Option<(K, V)> {
+        let pos = tree::root(&self.data)?;
+        Some(soa_heap::remove_at(&mut self.data, pos, &self.ord))
+    }
+
+    /// Removes a pair by its index and returns it, or `None` if the element is no longer in the heap.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(log(*n*))
+    pub fn remove(&mut self, index: Idx<V>) -> Option<(K, V)> {
+        let pos = self.data.try_index_to_pos(index)?;
+        Some(soa_heap::remove_at(&mut self.data, pos, &self.ord))
+    }
+
+    /// Replaces the key of an element, and reorders the heap.
+    ///
+    /// Returns the old key, or `None` if the element is no longer in the heap.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(log(*n*))
+    pub fn update_key(&mut self, index: Idx<V>, key: K) -> Option<K> {
+        let pos = self.data.try_index_to_pos(index)?;
+        let old = mem::replace(self.data.get_mut(pos), key);
+        self.data.fixup_sift(pos, &self.ord);
+        Some(old)
+    }
+
+    /// Replaces the key of an element with a key of higher (or equal) priority, moving it towards the top of the heap.
+    ///
+    /// Returns the old key, or `None` if the element is no longer in the heap.
+    ///
+    /// See [`IndexableHeap::decrease_key`] for details.
+    ///
+    /// # Panics
+    ///
+    /// In debug builds, if the new key has lower priority than the old one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mheap::{IndexableSoaHeap, MinHeap};
+    ///
+    /// let mut heap = IndexableSoaHeap::<u32, &str, MinHeap>::new();
+    /// heap.push(5, "a");
+    /// let idx = heap.push(7, "b");
+    ///
+    /// assert_eq!(heap.decrease_key(idx, 2), Some(7));
+    /// assert_eq!(heap.pop(), Some((2, "b")));
+    /// ```
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(log(*n*))
+    ///
+    /// [`IndexableHeap::decrease_key`]: crate::IndexableHeap::decrease_key
+    pub fn decrease_key(&mut self, index: Idx<V>, key: K) -> Option<K> {
+        let pos = self.data.try_index_to_pos(index)?;
+        debug_assert!(
+            !self.ord.should_sift_down(&key, self.data.get(pos)),
+            "decrease_key: the new key has lower priority than the old one"
+        );
+        let old = mem::replace(self.data.get_mut(pos), key);
+        self.data.sift_up(pos, &self.ord);
+        Some(old)
+    }
+
+    /// Replaces the key of an element with a key of lower (or equal) priority, moving it towards the bottom of the heap.
+    ///
+    /// Returns the old key, or `None` if the element is no longer in the heap.
+    ///
+    /// This is the counterpart of [`IndexableSoaHeap::decrease_key`].
+    ///
+    /// # Panics
+    ///
+    /// In debug builds, if the new key has higher priority than the old one.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(log(*n*))
+    pub fn increase_key(&mut self, index: Idx<V>, key: K) -> Option<K> {
+        let pos = self.data.try_index_to_pos(index)?;
+        debug_assert!(
+            !self.ord.should_sift_up(&key, self.data.get(pos)),
+            "increase_key: the new key has higher priority than the old one"
+        );
+        let old = mem::replace(self.data.get_mut(pos), key);
+        self.data.sift_down(pos, &self.ord);
+        Some(old)
+    }
+
+    /// Allows to modify many keys at once, restoring the heap invariant only once at the end.
+    ///
+    /// See [`IndexableHeap::batch_update`] for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mheap::{IndexableSoaHeap, MinHeap};
+    ///
+    /// let mut heap = IndexableSoaHeap::<i32, usize, MinHeap>::new();
+    /// let indices: Vec<_> = (0..10).map(|x| heap.push(x, x as usize)).collect();
+    ///
+    /// heap.batch_update(|batch| {
+    ///     for idx in &indices {
+    ///         if let Some(key) = batch.key_mut(*idx) {
+    ///             *key = 100 - *key;
+    ///         }
+    ///     }
+    /// });
+    /// assert_eq!(heap.pop(), Some((91, 9)));
+    /// ```
+    ///
+    /// # Time complexity
+    ///
+    /// For *k* modified keys, *O*(min(*n*, *k* log(*n*)<sup>2</sup>)).
+    ///
+    /// [`IndexableHeap::batch_update`]: crate::IndexableHeap::batch_update
+    pub fn batch_update<R, F: FnOnce(&mut BatchUpdate<'_, K, V, O, S>) -> R>(&mut self, f: F) -> R {
+        let mut batch = BatchUpdate {
+            heap: self,
+            dirty: Vec::new(),
+        };
+        f(&mut batch)
+    }
+
+    /// Consumes the heap and returns a vector of pairs in the pop order.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*n* log(*n*))
+    pub fn into_sorted_vec(mut self) -> Vec<(K, V)> {
+        let mut sorted = Vec::with_capacity(self.len());
+        while let Some(item) = self.pop() {
+            sorted.push(item);
+        }
+        sorted
+    }
+}
+
+impl<K, V, O: Default, S> Default for IndexableSoaHeap<K, V, O, S> {
+    /// Creates an empty heap.
+    fn default() -> Self {
+        Self {
+            data: Shaped::new(SoaVec::new()),
+            ord: O::default(),
+        }
+    }
+}
+
+impl<K, V, O: Ordering<K> + Default, S: Shape> From<Vec<(K, V)>> for IndexableSoaHeap<K, V, O, S> {
+    /// Converts a vector of `(key, value)` pairs into a heap in *O*(*n*) time.
+    ///
+    /// The issued indices are discarded.
+    /// Use [`IndexableSoaHeap::from_vec_with_ordering`] to obtain them.
+    fn from(vec: Vec<(K, V)>) -> Self {
+        Self::from_vec_in_shape(vec, O::default()).0
+    }
+}
+
+impl<K, V, O: Ordering<K> + Default, S: Shape, const N: usize> From<[(K, V); N]> for IndexableSoaHeap<K, V, O, S> {
+    /// Converts an array of `(key, value)` pairs into a heap in *O*(*n*) time.
+    fn from(arr: [(K, V); N]) -> Self {
+        Self::from(Vec::from(arr))
+    }
+}
+
+impl<K, V, O: Ordering<K> + Default, S: Shape> FromIterator<(K, V)> for IndexableSoaHeap<K, V, O, S> {
+    /// Collects the pairs into a heap.
+    ///
+    /// The issued indices are discarded.
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        Self::from(iter.into_iter().collect::<Vec<_>>())
+    }
+}
+
+impl<K, V, O: Ordering<K>, S: Shape> Extend<(K, V)> for IndexableSoaHeap<K, V, O, S> {
+    /// Pushes all pairs of the iterator onto the heap, discarding the issued indices.
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        for (key, value) in iter {
+            self.push(key, value);
+        }
+    }
+}
+
+impl<'a, K, V, O, S> IntoIterator for &'a IndexableSoaHeap<K, V, O, S> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = Iter<'a, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// A batch of key modifications of an [`IndexableSoaHeap`], that restores the heap invariant when dropped.
+///
+/// This `struct` is created by the [`batch_update`] method on [`IndexableSoaHeap`]. See
+/// its documentation for more.
+///
+/// [`batch_update`]: IndexableSoaHeap::batch_update
+pub struct BatchUpdate<'a, K, V, O: Ordering<K>, S: Shape = Binary> {
+    heap: &'a mut IndexableSoaHeap<K, V, O, S>,
+    dirty: Vec<Position>,
+}
+
+impl<K, V, O: Ordering<K>, S: Shape> Drop for BatchUpdate<'_, K, V, O, S> {
+    fn drop(&mut self) {
+        self.heap.data.rebuild_dirty(&mut self.dirty, &self.heap.ord);
+    }
+}
+
+impl<K, V, O: Ordering<K>, S: Shape> BatchUpdate<'_, K, V, O, S> {
+    /// Returns the number of elements in the heap.
+    pub fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    /// Returns `true` if the heap is empty.
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+
+    /// Returns `true` if the element with the given index is still in the heap.
+    ///
+    /// See [`IndexableSoaHeap::contains`].
+    pub fn contains(&self, index: Idx<V>) -> bool {
+        self.heap.contains(index)
+    }
+
+    /// Returns the pair by its index, or `None` if the element is no longer in the heap.
+    ///
+    /// See [`IndexableSoaHeap::get`].
+    pub fn get(&self, index: Idx<V>) -> Option<(&K, &V)> {
+        self.heap.get(index)
+    }
+
+    /// Returns a mutable reference to a key by its index, or `None` if the element is no longer in the heap.
+    ///
+    /// The element is marked as dirty, and is put into its new position at the end of the batch.
+    pub fn key_mut(&mut self, index: Idx<V>) -> Option<&mut K> {
+        let pos = self.heap.data.try_index_to_pos(index)?;
+        self.dirty.push(pos);
+        Some(self.heap.data.get_mut(pos))
+    }
+
+    /// Returns a mutable reference to a value by its index, or `None` if the element is no longer in the heap.
+    ///
+    /// See [`IndexableSoaHeap::value_mut`].
+    pub fn value_mut(&mut self, index: Idx<V>) -> Option<&mut V> {
+        self.heap.value_mut(index)
+    }
+}
@@ -1,5 +1,7 @@
-use std::{
+use alloc::vec::{self, Vec};
+use core::{
     fmt,
+    hash::{Hash, Hasher},
     marker::PhantomData,
     mem::{self, ManuallyDrop},
     ptr,
@@ -8,6 +10,9 @@ use std::{
 use crate::Position;
 
 type RawIdx = usize;
+/// Incremented every time a slot is freed, so a stale [`Idx`] never matches a reused slot.
+/// It is 64 bits wide, so it cannot wrap in practice. Next to a `usize`, it takes no more space than a padded `u32`
+pub(crate) type Generation = u64;
 
 /// An opaque handle to an element of type `T`.
 ///
@@ -21,8 +26,16 @@ type RawIdx = usize;
 /// If the element is removed from the heap, the index becomes invalid.
 /// Even when the element is then pushed back, the index might be different.
 ///
-/// When indexing with an invalid index, the heap might, might not panic.
-/// Since the heap reuses indices, indexing with stale index might just return some unrelated element.
+/// The heap reuses the storage of removed indices, but every index also carries a generation.
+/// So a stale index never resolves to an element pushed later:
+/// [`IndexableHeap::get`], [`IndexableHeap::contains`] and [`IndexableHeap::remove`]
+/// report it as invalid, and [`IndexableHeap::by_index_mut`] panics.
+///
+/// [`IndexableSoaHeap`] issues an `Idx<V>` of the value, by the same rules.
+/// [`IndexableCachedKeyHeap`] issues an `Idx<T>` of the element, without its cached key.
+///
+/// Indexing with an index obtained from a different heap is a logic error.
+/// The heap might panic, or return some unrelated element.
 ///
 /// # Examples
 ///
@@ -46,21 +59,35 @@ type RawIdx = usize;
 /// [`IndexableHeap`]: crate::IndexableHeap
 /// [`IndexableHeap::push`]: crate::IndexableHeap::push
 /// [`IndexableHeap::by_index_mut`]: crate::IndexableHeap::by_index_mut
-pub struct Idx<T>(RawIdx, PhantomData<T>);
+/// [`IndexableHeap::get`]: crate::IndexableHeap::get
+/// [`IndexableHeap::contains`]: crate::IndexableHeap::contains
+/// [`IndexableHeap::remove`]: crate::IndexableHeap::remove
+/// [`IndexableSoaHeap`]: crate::IndexableSoaHeap
+/// [`IndexableCachedKeyHeap`]: crate::IndexableCachedKeyHeap
+pub struct Idx<T>(RawIdx, Generation, PhantomData<T>);
 
 impl<T> Idx<T> {
-    fn new(index: RawIdx) -> Self {
-        Self(index, PhantomData)
+    pub(crate) fn new((index, generation): (RawIdx, Generation)) -> Self {
+        Self(index, generation, PhantomData)
     }
 
-    fn index(&self) -> usize {
+    pub(crate) fn index(&self) -> usize {
         self.0
     }
+
+    pub(crate) fn generation(&self) -> Generation {
+        self.1
+    }
+
+    /// The same handle, typed by what the heap exposes instead of what it stores
+    pub(crate) fn cast<U>(self) -> Idx<U> {
+        Idx(self.0, self.1, PhantomData)
+    }
 }
 
 impl<T> Clone for Idx<T> {
     fn clone(&self) -> Self {
-        Self(self.0, PhantomData)
+        *self
     }
 }
 
@@ -68,15 +95,22 @@ impl<T> Copy for Idx<T> {}
 
 impl<T> PartialEq for Idx<T> {
     fn eq(&self, other: &Self) -> bool {
-        self.0 == other.0
+        self.0 == other.0 && self.1 == other.1
     }
 }
 
 impl<T> Eq for Idx<T> {}
 
+impl<T> Hash for Idx<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.hash(state);
+        self.1.hash(state);
+    }
+}
+
 impl<T> fmt::Debug for Idx<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "Idx<{}>({})", std::any::type_name::<T>(), self.0)
+        write!(f, "Idx<{}>({}v{})", core::any::type_name::<T>(), self.0, self.1)
     }
 }
 
@@ -120,6 +154,23 @@ impl<T> IndexableVec<T> {
         &mut self.data[pos].0
     }
 
+    /// Elements together with their indices, in storage order
+    pub(crate) fn entries(&self) -> &[(T, Idx<T>)] {
+        &self.data
+    }
+
+    pub(crate) fn into_entries(self) -> Vec<(T, Idx<T>)> {
+        self.data
+    }
+
+    /// Removes all elements, invalidating their indices
+    pub(crate) fn drain(&mut self) -> vec::Drain<'_, (T, Idx<T>)> {
+        for (_, index) in &self.data {
+            self.position.remove(index.index());
+        }
+        self.data.drain(..)
+    }
+
     pub(crate) fn push(&mut self, item: T) -> Idx<T> {
         let pos = self.data.len();
         let index = Idx::new(self.position.add(pos));
@@ -127,19 +178,15 @@ impl<T> IndexableVec<T> {
         index
     }
 
-    pub(crate) fn pop(&mut self) -> Option<T> {
-        let (item, index) = self.data.pop()?;
-        // SAFETY: structure invariant
-        unsafe { self.assert_index(self.data.len(), index) };
-        self.position.remove(index.index());
-        Some(item)
-    }
-
     pub(crate) fn swap_remove(&mut self, pos: Position) -> T {
         let (item, index) = self.data.swap_remove(pos);
         // SAFETY: structure invariant
         unsafe { self.assert_index(pos, index) };
         self.position.remove(index.index());
+        // The last element was moved into the `pos`
+        if pos < self.data.len() {
+            self.record_position(pos);
+        }
         item
     }
 
@@ -168,28 +215,46 @@ impl<T> IndexableVec<T> {
         self.position.set(index.index(), pos);
     }
 
+    /// Panics if the index is not in use
     pub(crate) fn index_to_pos(&self, index: Idx<T>) -> Position {
+        return match self.try_index_to_pos(index) {
+            Some(pos) => pos,
+            None => handle_invalid(index),
+        };
+
+        #[cold]
+        #[inline(never)]
+        fn handle_invalid<T>(index: Idx<T>) -> ! {
+            panic!("{index:?} is no longer in the heap");
+        }
+    }
+
+    /// Returns `None` if the index is not in use
+    pub(crate) fn try_index_to_pos(&self, index: Idx<T>) -> Option<Position> {
+        if !self.position.is_valid(index.index(), index.generation()) {
+            return None;
+        }
         let pos = self.position.get(index.index());
         // SAFETY: position map contains only valid positions
         unsafe { self.assert_pos(index, pos) };
-        pos
+        Some(pos)
     }
 
     pub(crate) fn pos_to_index(&self, pos: Position) -> Idx<T> {
         let index = self.data[pos].1;
         // SAFETY: data contains only valid indices
         unsafe { self.assert_index(pos, index) };
-        return index;
+        index
     }
 
     /// Must ensure index is valid
     unsafe fn assert_index(&self, pos: Position, index: Idx<T>) {
-        if !self.position.is_valid(index.index()) {
+        if !self.position.is_valid(index.index(), index.generation()) {
             if cfg!(debug_assertions) {
                 panic!("position {pos} contains invalid index {}", index.index());
             }
             unsafe {
-                std::hint::unreachable_unchecked();
+                core::hint::unreachable_unchecked();
             }
         }
     }
@@ -205,7 +270,7 @@ impl<T> IndexableVec<T> {
                 );
             }
             unsafe {
-                std::hint::unreachable_unchecked();
+                core::hint::unreachable_unchecked();
             }
         }
     }
@@ -258,7 +323,10 @@ struct SkipList {
     first_skip: NextSkip,
 }
 
-struct SkipEntry(usize);
+struct SkipEntry {
+    value: usize,
+    generation: Generation,
+}
 #[derive(Clone, Copy)]
 struct NextSkip(usize);
 
@@ -282,24 +350,30 @@ impl NextSkip {
 impl SkipEntry {
     const SKIP_BIT: usize = isize::MIN as usize;
 
-    fn from_pos(pos: Position) -> Option<Self> {
+    fn from_pos(pos: Position, generation: Generation) -> Option<Self> {
         if pos & Self::SKIP_BIT == 0 {
-            Some(Self(pos))
+            Some(Self {
+                value: pos,
+                generation,
+            })
         } else {
             None
         }
     }
 
-    fn from_skip(next_idx: NextSkip) -> Self {
-        Self(next_idx.0 | Self::SKIP_BIT)
+    fn from_skip(next_idx: NextSkip, generation: Generation) -> Self {
+        Self {
+            value: next_idx.0 | Self::SKIP_BIT,
+            generation,
+        }
     }
 
     fn repr(&self) -> SkipEntryRepr {
-        if self.0 & Self::SKIP_BIT == 0 {
-            SkipEntryRepr::Data(self.0)
+        if self.value & Self::SKIP_BIT == 0 {
+            SkipEntryRepr::Data(self.value)
         } else {
             SkipEntryRepr::Skip {
-                next_idx: NextSkip(self.0 & !Self::SKIP_BIT),
+                next_idx: NextSkip(self.value & !Self::SKIP_BIT),
             }
         }
     }
@@ -366,23 +440,26 @@ impl SkipList {
         self.data.shrink_to(min_capacity);
     }
 
-    fn add(&mut self, pos: Position) -> RawIdx {
-        let pos = SkipEntry::from_pos(pos).unwrap();
+    fn add(&mut self, pos: Position) -> (RawIdx, Generation) {
         if let Some(index) = self.first_skip.get() {
             // SAFETY: all skip entries must always be valid
             unsafe { self.assert_index(index) };
+            let generation = self.data[index].generation;
+            let pos = SkipEntry::from_pos(pos, generation).unwrap();
             let entry = mem::replace(&mut self.data[index], pos);
             self.first_skip = entry.expect_skip();
-            index
+            (index, generation)
         } else {
             let index = self.data.len();
-            self.data.push(pos);
-            index
+            self.data.push(SkipEntry::from_pos(pos, 0).unwrap());
+            (index, 0)
         }
     }
 
-    fn is_valid(&self, index: RawIdx) -> bool {
-        self.data.get(index).is_some_and(|it| it.is_data())
+    fn is_valid(&self, index: RawIdx, generation: Generation) -> bool {
+        self.data
+            .get(index)
+            .is_some_and(|it| it.is_data() && it.generation == generation)
     }
 
     fn get(&self, index: RawIdx) -> Position {
@@ -390,20 +467,20 @@ impl SkipList {
     }
 
     fn set(&mut self, index: RawIdx, pos: Position) {
-        self.data[index].expect_data();
-        self.data[index] = SkipEntry::from_pos(pos).unwrap();
+        let entry = &mut self.data[index];
+        entry.expect_data();
+        *entry = SkipEntry::from_pos(pos, entry.generation).unwrap();
     }
 
     fn remove(&mut self, index: RawIdx) -> Position {
-        if index == self.data.len() - 1 {
-            // We do not need to touch `self.first_skip`, since it cannot point to the last element (it is data, not skip)
-            self.data.pop().unwrap().expect_data()
-        } else {
-            let new_skip = SkipEntry::from_skip(self.first_skip);
-            let pos = mem::replace(&mut self.data[index], new_skip).expect_data();
-            self.first_skip = NextSkip::some(index);
-            pos
-        }
+        // We never shrink the list, even if the last entry is removed,
+        // as the generation of the entry must survive until it is reused.
+        let entry = &mut self.data[index];
+        let generation = entry.generation.wrapping_add(1);
+        let new_skip = SkipEntry::from_skip(self.first_skip, generation);
+        let pos = mem::replace(entry, new_skip).expect_data();
+        self.first_skip = NextSkip::some(index);
+        pos
     }
 
     /// Must ensure index is valid
@@ -413,7 +490,7 @@ impl SkipList {
                 panic!("index {index} is out of bounds");
             }
             unsafe {
-                std::hint::unreachable_unchecked();
+                core::hint::unreachable_unchecked();
             }
         }
     }
@@ -0,0 +1,283 @@
+//! A heap of `(key, value)` pairs, where only the key is compared.
+//!
+//! See [`KeyValueHeap`] for details.
+
+use alloc::vec::Vec;
+use core::{iter::FusedIterator, slice};
+
+use crate::{ConstDefault, RawHeap, key_value_vec::KeyValueVec, ordering::Ordering, storage::Storage, tree};
+
+/// A heap of `(key, value)` pairs, where only the key is compared.
+///
+/// The ordering sees only the keys, i.e. the priorities. So the values need no [`Ord`] implementation,
+/// and there is no need for a [`by_key`] closure.
+///
+/// Use the `O` generic parameter to select [`MaxHeap`] or [`MinHeap`].
+/// See [`crate::ordering`] for details.
+///
+/// # Examples
+///
+/// ```
+/// use mheap::{KeyValueHeap, MinHeap};
+///
+/// struct Job(&'static str); // not `Ord`
+///
+/// let mut heap = KeyValueHeap::<u32, Job, MinHeap>::new();
+/// heap.push(3, Job("c"));
+/// heap.push(1, Job("a"));
+/// heap.push(2, Job("b"));
+///
+/// let (priority, job) = heap.pop().unwrap();
+/// assert_eq!((priority, job.0), (1, "a"));
+/// assert_eq!(heap.peek().map(|(k, _)| *k), Some(2));
+/// ```
+///
+/// # Time complexity
+///
+/// | Operation | Time complexity |
+/// |-----------|----------------|
+/// | `push`    | *O*(1)~        |
+/// | `pop`     | *O*(log(*n*))  |
+/// | `peek`    | *O*(1)         |
+///
+/// The value of `push` is an expected complexity.
+///
+/// [`by_key`]: crate::MaxHeap::by_key
+/// [`MaxHeap`]: crate::MaxHeap
+/// [`MinHeap`]: crate::MinHeap
+pub struct KeyValueHeap<K, V, O> {
+    data: KeyValueVec<K, V>,
+    ord: O,
+}
+
+impl<K, V, O> KeyValueHeap<K, V, O> {
+    /// Creates a new empty heap
+    pub const fn new() -> Self
+    where
+        O: ConstDefault,
+    {
+        Self::with_ordering(O::DEFAULT)
+    }
+
+    /// Creates a new empty heap with the specified capacity
+    ///
+    /// The heap will be able to hold at least `capacity` elements without reallocating.
+    pub fn with_capacity(capacity: usize) -> Self
+    where
+        O: Default,
+    {
+        Self::with_capacity_and_ordering(capacity, O::default())
+    }
+
+    /// Creates a new empty heap with the specified ordering of the keys.
+    pub const fn with_ordering(ord: O) -> Self {
+        Self {
+            data: KeyValueVec::new(),
+            ord,
+        }
+    }
+
+    /// Creates a new empty heap with the specified capacity and ordering of the keys.
+    ///
+    /// The heap will be able to hold at least `capacity` elements without reallocating.
+    pub fn with_capacity_and_ordering(capacity: usize, ord: O) -> Self {
+        Self {
+            data: KeyValueVec::with_capacity(capacity),
+            ord,
+        }
+    }
+
+    /// Creates a heap from a vector of pairs with the specified ordering of the keys.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*n*)
+    pub fn from_vec_with_ordering(vec: Vec<(K, V)>, ord: O) -> Self
+    where
+        O: Ordering<K>,
+    {
+        let mut data = KeyValueVec::from_vec(vec);
+        data.rebuild(&ord);
+        Self { data, ord }
+    }
+
+    /// Returns the number of elements in the heap.
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Returns `true` if the heap is empty.
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Returns the number of elements the heap can hold without reallocating.
+    pub fn capacity(&self) -> usize {
+        self.data.capacity()
+    }
+
+    /// Removes all elements from the heap.
+    pub fn clear(&mut self) {
+        self.data.clear();
+    }
+
+    /// Returns an iterator visiting all `(key, value)` pairs, in arbitrary order.
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter {
+            iter: self.data.as_slice().iter(),
+        }
+    }
+
+    /// Consumes the heap and returns the pairs in arbitrary order.
+    pub fn into_vec(self) -> Vec<(K, V)> {
+        self.data.into_vec()
+    }
+}
+
+impl<K, V, O: Ordering<K>> KeyValueHeap<K, V, O> {
+    /// Returns the top pair, or `None` if the heap is empty.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(1)
+    pub fn peek(&self) -> Option<(&K, &V)> {
+        let (key, value) = self.data.peek()?;
+        Some((key, value))
+    }
+
+    /// Returns the top pair with a mutable value, or `None` if the heap is empty.
+    ///
+    /// The key cannot be modified, so the heap never needs to be reordered.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mheap::{KeyValueHeap, MaxHeap};
+    ///
+    /// let mut heap = KeyValueHeap::<u32, Vec<&str>, MaxHeap>::new();
+    /// heap.push(1, vec![]);
+    /// heap.push(5, vec![]);
+    ///
+    /// if let Some((_, tags)) = heap.peek_mut() {
+    ///     tags.push("urgent");
+    /// }
+    /// assert_eq!(heap.pop(), Some((5, vec!["urgent"])));
+    /// ```
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(1)
+    pub fn peek_mut(&mut self) -> Option<(&K, &mut V)> {
+        let pos = tree::root(&self.data)?;
+        let (key, value) = self.data.get_mut(pos);
+        Some((key, value))
+    }
+
+    /// Pushes a `(key, value)` pair onto the heap.
+    ///
+    /// # Time complexity
+    ///
+    /// The expected cost of `push` is *O*(1), and the worst case is *O*(log(*n*)).
+    pub fn push(&mut self, key: K, value: V) {
+        let pos = self.data.len();
+        self.data.push(key, value);
+        self.data.sift_up(pos, &self.ord);
+    }
+
+    /// Removes the top pair from the heap and returns it, or `None` if it is empty.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(log(*n*))
+    pub fn pop(&mut self) -> Option<(K, V)> {
+        let item = self.data.pop()?;
+        Some(self.data.pop_swap(item, &self.ord))
+    }
+
+    /// Consumes the heap and returns a vector of pairs in the pop order.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*n* log(*n*))
+    pub fn into_sorted_vec(mut self) -> Vec<(K, V)> {
+        let mut sorted = Vec::with_capacity(self.len());
+        while let Some(item) = self.pop() {
+            sorted.push(item);
+        }
+        sorted
+    }
+}
+
+impl<K, V, O: Default> Default for KeyValueHeap<K, V, O> {
+    /// Creates an empty heap.
+    fn default() -> Self {
+        Self::with_ordering(O::default())
+    }
+}
+
+impl<K, V, O: Ordering<K> + Default> From<Vec<(K, V)>> for KeyValueHeap<K, V, O> {
+    /// Builds a heap in *O*(*n*) time.
+    fn from(vec: Vec<(K, V)>) -> Self {
+        Self::from_vec_with_ordering(vec, O::default())
+    }
+}
+
+impl<K, V, O: Ordering<K> + Default> FromIterator<(K, V)> for KeyValueHeap<K, V, O> {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        Self::from(iter.into_iter().collect::<Vec<_>>())
+    }
+}
+
+impl<K, V, O: Ordering<K>> Extend<(K, V)> for KeyValueHeap<K, V, O> {
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        for (key, value) in iter {
+            self.push(key, value);
+        }
+    }
+}
+
+impl<'a, K, V, O> IntoIterator for &'a KeyValueHeap<K, V, O> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = Iter<'a, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// An iterator over the pairs of a [`KeyValueHeap`], in arbitrary order.
+///
+/// This `struct` is created by [`KeyValueHeap::iter`].
+pub struct Iter<'a, K, V> {
+    iter: slice::Iter<'a, (K, V)>,
+}
+
+impl<K, V> Clone for Iter<'_, K, V> {
+    fn clone(&self) -> Self {
+        Self {
+            iter: self.iter.clone(),
+        }
+    }
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().map(|(key, value)| (key, value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<K, V> DoubleEndedIterator for Iter<'_, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.iter.next_back().map(|(key, value)| (key, value))
+    }
+}
+
+impl<K, V> ExactSizeIterator for Iter<'_, K, V> {}
+
+impl<K, V> FusedIterator for Iter<'_, K, V> {}
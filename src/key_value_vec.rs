@@ -0,0 +1,92 @@
+use alloc::vec::Vec;
+use core::mem::ManuallyDrop;
+
+use crate::{Position, storage::Storage};
+
+/// A vector of `(key, value)` pairs, where only the key is compared
+pub(crate) struct KeyValueVec<K, V> {
+    data: Vec<(K, V)>,
+}
+
+impl<K, V> KeyValueVec<K, V> {
+    pub(crate) const fn new() -> Self {
+        Self { data: Vec::new() }
+    }
+
+    pub(crate) fn with_capacity(capacity: usize) -> Self {
+        Self {
+            data: Vec::with_capacity(capacity),
+        }
+    }
+
+    pub(crate) fn from_vec(data: Vec<(K, V)>) -> Self {
+        Self { data }
+    }
+
+    pub(crate) fn into_vec(self) -> Vec<(K, V)> {
+        self.data
+    }
+
+    pub(crate) fn as_slice(&self) -> &[(K, V)] {
+        &self.data
+    }
+
+    pub(crate) fn capacity(&self) -> usize {
+        self.data.capacity()
+    }
+
+    pub(crate) fn push(&mut self, key: K, value: V) {
+        self.data.push((key, value));
+    }
+
+    pub(crate) fn pop(&mut self) -> Option<(K, V)> {
+        self.data.pop()
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.data.clear();
+    }
+}
+
+unsafe impl<K, V> Storage for KeyValueVec<K, V> {
+    fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    type Item = (K, V);
+
+    type Key = K;
+
+    fn key(item: &Self::Item) -> &Self::Key {
+        &item.0
+    }
+
+    fn get(&self, pos: Position) -> &Self::Item {
+        &self.data[pos]
+    }
+
+    fn get_mut(&mut self, pos: Position) -> &mut Self::Item {
+        &mut self.data[pos]
+    }
+
+    type Slot = (K, V);
+
+    fn slot_key(item: &Self::Slot) -> &Self::Key {
+        &item.0
+    }
+
+    unsafe fn load(&self, pos: Position) -> ManuallyDrop<Self::Slot> {
+        // SAFETY: forwards to the underlying slice
+        unsafe { self.data.as_slice().load(pos) }
+    }
+
+    unsafe fn store(&mut self, pos: Position, item: &mut ManuallyDrop<Self::Slot>) {
+        // SAFETY: forwards to the underlying slice
+        unsafe { self.data.as_mut_slice().store(pos, item) }
+    }
+
+    unsafe fn move_element(&mut self, src: Position, dst: Position) {
+        // SAFETY: forwards to the underlying slice
+        unsafe { self.data.as_mut_slice().move_element(src, dst) }
+    }
+}
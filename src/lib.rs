@@ -1,9 +1,10 @@
+#![no_std]
 #![deny(unsafe_op_in_unsafe_fn)]
 
 //! This crate provides classical binary heaps.
-//! Like [`std::collections::BinaryHeap`], but with more flexibility oriented design.
+//! Like [`alloc::collections::BinaryHeap`], but with more flexibility oriented design.
 //!
-//! General api is similar to [`std::collections::BinaryHeap`]:
+//! General api is similar to [`alloc::collections::BinaryHeap`]:
 //! ```
 //! # use mheap::{VecHeap, MaxHeap};
 //!
@@ -23,14 +24,24 @@
 //!
 //! First you select the heap `storage`.
 //! It represents how the heap is stored in memory and what additional operations are needed.
-//! Currently there are two storages:
-//! * [`VecHeap`] - stores elements in a plain [`Vec`] and nothing else. Analogous to [`std::collections::BinaryHeap`].
+//! The crate provides these storages:
+//! * [`VecHeap`] - stores elements in a plain [`Vec`] and nothing else. Analogous to [`alloc::collections::BinaryHeap`].
 //! * [`IndexableHeap`] - similar to [`VecHeap`], but allows to access elements by an opaque [`Idx`]
+//! * [`KeyedHeap`] - a priority queue built on [`IndexableHeap`], that allows to access priorities by a user-provided key. Requires the `std` feature.
+//!   [`BTreeKeyedHeap`] is the same, but looks up ordered keys in a `BTreeMap`, so it needs only `alloc`
+//! * [`KeyValueHeap`] - stores `(key, value)` pairs, and compares only the keys, so the values need no ordering
+//! * [`SoaHeap`] - similar to [`KeyValueHeap`], but keeps the keys and the values in separate arrays, so large values are never moved. [`IndexableSoaHeap`] also allows access by index
+//! * [`DenseIndexedHeap`] - similar to [`IndexableHeap`], but elements are identified by small integer ids chosen by the caller
+//! * [`MinMaxHeap`] - a double-ended priority queue, with access to both the smallest and the largest element. [`IndexableMinMaxHeap`] also allows access by index
+//! * [`ArrayHeap`] - similar to [`VecHeap`], but stored inline in an array of a fixed capacity, so it never allocates
+//! * [`BoundedHeap`] - keeps only the best `k` elements offered to it, e.g. to select the top `k` of a stream
+//! * [`CachedKeyHeap`] - similar to [`VecHeap`], but computes the key of each element once, instead of on every comparison. [`IndexableCachedKeyHeap`] also allows access by index
+//! * [`StableHeap`] - similar to [`VecHeap`], but pops equal elements in the insertion order
 //!
 //! Then you select how the elements should be sorted - an [`Ordering`].
 //! Two primary orderings are:
-//! * [`MaxHeap`] - puts largest element on top of the heap. Like the [`std::collections::BinaryHeap`].
-//! * [`MinHeap`] - puts smallest element on top of the heap. Like the [`std::collections::BinaryHeap`] with [`Reverse`] wrapper.
+//! * [`MaxHeap`] - puts largest element on top of the heap. Like the [`alloc::collections::BinaryHeap`].
+//! * [`MinHeap`] - puts smallest element on top of the heap. Like the [`alloc::collections::BinaryHeap`] with [`Reverse`] wrapper.
 //!
 //! You can also compare elements by ad-hoc orderings. For example:
 //! ```
@@ -50,30 +61,89 @@
 //!
 //! See [`MaxHeap`] and [`MinHeap`] for details.
 //!
+//! All heaps are binary by default. Heaps with more children per node can be selected
+//! with a [`shape`] parameter, e.g. `VecHeap<u64, MinHeap, DAry<4>>`.
+//!
+//! Finally, if none of the provided storages fits, you can implement your own [`Storage`].
+//! The heap algorithms are then available via the [`RawHeap`] trait.
+//!
+//! The crate is `no_std`, and needs only the `alloc` crate.
+//! The `std` feature is enabled by default. It adds [`KeyedHeap`], which is backed by a `HashMap`
+//! (without `std`, use [`BTreeKeyedHeap`], which needs `Ord` keys instead of `Hash + Eq`),
+//! and the debug check of an unrestored [`raw_heap::PeekMut`], which needs to know if the thread is panicking.
+//! To use the crate without `std`, disable the default features:
+//! ```toml
+//! mheap = { version = "0.1", default-features = false }
+//! ```
+//!
 //! [`Idx`]: indexable_heap::Idx
-//! [`Reverse`]: std::cmp::Reverse
+//! [`Vec`]: alloc::vec::Vec
+//! [`Reverse`]: core::cmp::Reverse
 //! [`Ordering`]: crate::ordering::Ordering
+#![cfg_attr(
+    not(feature = "std"),
+    doc = "[`KeyedHeap`]: https://docs.rs/mheap/latest/mheap/keyed_heap/struct.KeyedHeap.html"
+)]
+
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
 
 mod hole;
 pub mod ordering;
+pub mod shape;
 mod sift;
-mod storage;
+pub mod storage;
 mod tree;
 
-mod raw_heap;
+pub mod raw_heap;
 
+pub mod array_heap;
+mod array_vec;
+pub mod bounded_heap;
+pub mod btree_keyed_heap;
+pub mod cached_key_heap;
+pub mod dense_heap;
+mod dense_vec;
+pub mod indexable_cached_key_heap;
 pub mod indexable_heap;
+pub mod indexable_min_max_heap;
+pub mod indexable_soa_heap;
 mod indexable_vec;
+pub mod key_value_heap;
+mod key_value_vec;
+#[cfg(feature = "std")]
+pub mod keyed_heap;
+mod min_max;
+pub mod min_max_heap;
+pub mod soa_heap;
+mod soa_vec;
+pub mod stable_heap;
 pub mod vec_heap;
 
-pub(crate) use raw_heap::RawHeap;
+pub use crate::{raw_heap::RawHeap, storage::Storage};
 
+#[cfg(feature = "std")]
+pub use crate::keyed_heap::KeyedHeap;
 pub use crate::{
+    array_heap::ArrayHeap,
+    bounded_heap::BoundedHeap,
+    btree_keyed_heap::BTreeKeyedHeap,
+    cached_key_heap::CachedKeyHeap,
+    dense_heap::DenseIndexedHeap,
+    indexable_cached_key_heap::IndexableCachedKeyHeap,
     indexable_heap::IndexableHeap,
+    indexable_min_max_heap::IndexableMinMaxHeap,
+    indexable_soa_heap::IndexableSoaHeap,
+    key_value_heap::KeyValueHeap,
+    min_max_heap::MinMaxHeap,
     ordering::{MaxHeap, MinHeap},
+    soa_heap::SoaHeap,
+    stable_heap::StableHeap,
     vec_heap::VecHeap,
 };
 
+/// A position of an element in a [`Storage`].
 pub type Position = usize;
 
 /// A hack to have [`Default`] trait in const contexts. Used for [`Ordering`] impls.
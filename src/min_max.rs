@@ -0,0 +1,137 @@
+//! Algorithms of a min-max heap.
+//!
+//! A min-max heap is a binary tree, where the levels alternate between *min* and *max* levels,
+//! starting with a min level at the root.
+//! An element on a min level is the smallest in its subtree,
+//! and an element on a max level is the largest in its subtree.
+//! So the smallest element is the root, and the largest is one of its children.
+//!
+//! Unlike [`sift`], the tree is always binary, regardless of [`Storage::ARITY`].
+//!
+//! [`sift`]: crate::sift
+
+use crate::{Position, ordering::Cmp, storage::Storage};
+
+fn parent(pos: Position) -> Option<Position> {
+    Some(pos.checked_sub(1)? / 2)
+}
+
+fn grandparent(pos: Position) -> Option<Position> {
+    parent(parent(pos)?)
+}
+
+pub(crate) fn is_min_level(pos: Position) -> bool {
+    (pos + 1).ilog2().is_multiple_of(2)
+}
+
+/// Is the element at `a` strictly better than at `b` for a min (or max) level?
+fn better<S: Storage + ?Sized>(
+    data: &S,
+    cmp: &impl Cmp<S::Key>,
+    a: Position,
+    b: Position,
+    min: bool,
+) -> bool {
+    let ord = cmp.cmp(data.get_key(a), data.get_key(b));
+    if min { ord.is_lt() } else { ord.is_gt() }
+}
+
+/// Swaps two different elements through the hole operations,
+/// so the storage can keep track of the positions.
+fn swap<S: Storage + ?Sized>(data: &mut S, a: Position, b: Position) {
+    assert!(a != b && a < data.len() && b < data.len());
+    // SAFETY: both positions are in bounds and different, and there are no holes.
+    //         `load` creates a hole at `a`, `move_element` moves it to `b`,
+    //         and `store` fills it. None of them panics.
+    unsafe {
+        let mut elt = data.load(a);
+        data.move_element(b, a);
+        data.store(b, &mut elt);
+    }
+}
+
+/// Returns the position of the largest element, or `None` if the heap is empty.
+pub(crate) fn max_pos<S: Storage + ?Sized>(data: &S, cmp: &impl Cmp<S::Key>) -> Option<Position> {
+    match data.len() {
+        0 => None,
+        1 => Some(0),
+        2 => Some(1),
+        _ => Some(if better(data, cmp, 2, 1, false) { 2 } else { 1 }),
+    }
+}
+
+/// Moves the element at `pos` up along the levels of the same kind.
+///
+/// Returns the new position of the element.
+fn bubble_up<S: Storage + ?Sized>(
+    data: &mut S,
+    cmp: &impl Cmp<S::Key>,
+    mut pos: Position,
+    min: bool,
+) -> Position {
+    while let Some(grandparent) = grandparent(pos) {
+        if !better(data, cmp, pos, grandparent, min) {
+            break;
+        }
+        swap(data, pos, grandparent);
+        pos = grandparent;
+    }
+    pos
+}
+
+/// Moves the element at `pos` down, assuming that its subtrees are valid min-max heaps.
+fn trickle_down<S: Storage + ?Sized>(data: &mut S, cmp: &impl Cmp<S::Key>, mut pos: Position) {
+    let min = is_min_level(pos);
+    let len = data.len();
+    loop {
+        // Select the best of the children and grandchildren
+        let first_child = 2 * pos + 1;
+        let first_grandchild = 4 * pos + 3;
+        let descendants = (first_child..(first_child + 2).min(len))
+            .chain(first_grandchild..(first_grandchild + 4).min(len));
+        let Some(best) = descendants.reduce(|best, it| {
+            if better(data, cmp, it, best, min) { it } else { best }
+        }) else {
+            return;
+        };
+
+        if !better(data, cmp, best, pos, min) {
+            return;
+        }
+        swap(data, best, pos);
+        if best < first_grandchild {
+            // A child has no descendants on the levels of the same kind
+            return;
+        }
+
+        // The element is now on a level of the same kind, but it may be out of order with its parent
+        let parent = parent(best).unwrap();
+        if better(data, cmp, best, parent, !min) {
+            swap(data, best, parent);
+        }
+        pos = best;
+    }
+}
+
+/// Restores the heap invariant, after the element at `pos` was replaced with an arbitrary value.
+pub(crate) fn fixup<S: Storage + ?Sized>(data: &mut S, cmp: &impl Cmp<S::Key>, pos: Position) {
+    let min = is_min_level(pos);
+    if let Some(parent) = parent(pos)
+        && better(data, cmp, pos, parent, !min)
+    {
+        // The element belongs to the levels of the other kind.
+        // The old parent moves down, and may be out of order with its new subtree.
+        swap(data, pos, parent);
+        bubble_up(data, cmp, parent, !min);
+        trickle_down(data, cmp, pos);
+    } else if bubble_up(data, cmp, pos, min) == pos {
+        trickle_down(data, cmp, pos);
+    }
+}
+
+/// Restores the heap invariant for all elements, in *O*(*n*) time.
+pub(crate) fn rebuild<S: Storage + ?Sized>(data: &mut S, cmp: &impl Cmp<S::Key>) {
+    for pos in (0..data.len() / 2).rev() {
+        trickle_down(data, cmp, pos);
+    }
+}
@@ -0,0 +1,484 @@
+//! A double-ended priority queue, with access to both the smallest and the largest element.
+//!
+//! See [`MinMaxHeap`] for details.
+
+use alloc::vec::Vec;
+use core::{
+    cmp,
+    iter::FusedIterator,
+    ops::{Deref, DerefMut},
+    slice,
+};
+
+use crate::{
+    Position, min_max,
+    ordering::{ByCmp, ByKey, Cmp, Natural},
+};
+
+/// A double-ended priority queue, with access to both the smallest and the largest element.
+///
+/// It is stored in a [`Vec`] as a min-max heap: a binary tree, where the levels alternate
+/// between the smallest and the largest elements of their subtrees.
+///
+/// Unlike [`VecHeap`], it does not take an [`Ordering`], as it has no single top.
+/// Instead the `C` generic parameter selects how the elements are compared:
+/// [`Natural`] by default, a custom comparison via [`MinMaxHeap::by`] and [`MinMaxHeap::by_key`],
+/// or any other [`Cmp`] via [`MinMaxHeap::with_cmp`].
+///
+/// # Examples
+///
+/// ```
+/// use mheap::MinMaxHeap;
+///
+/// let mut heap = MinMaxHeap::new();
+/// heap.push(3);
+/// heap.push(1);
+/// heap.push(5);
+/// heap.push(4);
+///
+/// assert_eq!(heap.peek_min(), Some(&1));
+/// assert_eq!(heap.peek_max(), Some(&5));
+///
+/// assert_eq!(heap.pop_max(), Some(5));
+/// assert_eq!(heap.pop_min(), Some(1));
+/// assert_eq!(heap.pop_max(), Some(4));
+/// assert_eq!(heap.pop_max(), Some(3));
+/// assert_eq!(heap.pop_min(), None);
+/// ```
+///
+/// # Time complexity
+///
+/// | Operation               | Time complexity |
+/// |-------------------------|-----------------|
+/// | `push`                  | *O*(1)~         |
+/// | `pop_min`, `pop_max`    | *O*(log(*n*))   |
+/// | `peek_min`, `peek_max`  | *O*(1)          |
+///
+/// The value of `push` is an expected complexity.
+///
+/// [`VecHeap`]: crate::VecHeap
+/// [`Ordering`]: crate::ordering::Ordering
+pub struct MinMaxHeap<T, C = Natural> {
+    data: Vec<T>,
+    cmp: C,
+}
+
+impl<T> MinMaxHeap<T> {
+    /// Creates a new empty heap, that uses the default `Ord` implementation for comparison.
+    pub const fn new() -> Self {
+        Self::with_cmp(Natural)
+    }
+
+    /// Creates a new empty heap with the specified capacity.
+    ///
+    /// The heap will be able to hold at least `capacity` elements without reallocating.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            data: Vec::with_capacity(capacity),
+            cmp: Natural,
+        }
+    }
+}
+
+impl<T, C> MinMaxHeap<T, C> {
+    /// Creates a new empty heap, that compares elements by any [`Cmp`] from [`ordering`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mheap::{MinMaxHeap, ordering::ByTotalKey};
+    ///
+    /// let mut heap = MinMaxHeap::with_cmp(ByTotalKey::new(|x: &f64| *x));
+    /// heap.extend([2.5, -1.0, 7.0]);
+    /// assert_eq!(heap.peek_min(), Some(&-1.0));
+    /// assert_eq!(heap.peek_max(), Some(&7.0));
+    /// ```
+    ///
+    /// [`ordering`]: crate::ordering
+    pub const fn with_cmp(cmp: C) -> Self {
+        Self {
+            data: Vec::new(),
+            cmp,
+        }
+    }
+
+    /// Creates a new empty heap with the specified capacity and comparison.
+    ///
+    /// The heap will be able to hold at least `capacity` elements without reallocating.
+    pub fn with_capacity_and_cmp(capacity: usize, cmp: C) -> Self {
+        Self {
+            data: Vec::with_capacity(capacity),
+            cmp,
+        }
+    }
+
+    pub(crate) fn cmp(&self) -> &C {
+        &self.cmp
+    }
+
+    /// Returns the number of elements in the heap.
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Returns `true` if the heap is empty.
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Removes all elements from the heap.
+    pub fn clear(&mut self) {
+        self.data.clear();
+    }
+
+    /// Returns an iterator visiting all values in the underlying vector, in arbitrary order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mheap::MinMaxHeap;
+    ///
+    /// let heap = MinMaxHeap::<_>::from([1, 2, 3, 4]);
+    /// assert_eq!(heap.iter().sum::<i32>(), 10);
+    /// ```
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            iter: self.data.iter(),
+        }
+    }
+
+    /// Consumes the heap and returns the underlying vector in arbitrary order.
+    pub fn into_vec(self) -> Vec<T> {
+        self.data
+    }
+}
+
+impl<T, F: Fn(&T, &T) -> cmp::Ordering> MinMaxHeap<T, ByCmp<F>> {
+    /// Creates a new empty heap with a custom comparison function.
+    ///
+    /// The comparsion function is equivalent to the `Ord::cmp` implementation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mheap::MinMaxHeap;
+    ///
+    /// let mut heap = MinMaxHeap::by(|a: &i32, b: &i32| a.abs().cmp(&b.abs()));
+    /// heap.extend([3, -5, 1]);
+    /// assert_eq!(heap.peek_min(), Some(&1));
+    /// assert_eq!(heap.peek_max(), Some(&-5));
+    /// ```
+    pub fn by(cmp: F) -> Self {
+        Self::with_cmp(ByCmp(cmp))
+    }
+}
+
+impl<T, F> MinMaxHeap<T, ByKey<F>> {
+    /// Creates a new empty heap, that compares elements by a key extraction function.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mheap::MinMaxHeap;
+    ///
+    /// let mut heap = MinMaxHeap::by_key(|item: &(&str, u32)| item.1);
+    /// heap.push(("low", 1));
+    /// heap.push(("high", 10));
+    /// heap.push(("medium", 5));
+    /// assert_eq!(heap.pop_min(), Some(("low", 1)));
+    /// assert_eq!(heap.pop_max(), Some(("high", 10)));
+    /// ```
+    pub fn by_key<K: Ord>(key: F) -> Self
+    where
+        F: Fn(&T) -> K,
+    {
+        Self::with_cmp(ByKey(key))
+    }
+}
+
+impl<T, C: Cmp<T>> MinMaxHeap<T, C> {
+    /// Creates a heap from a vector in *O*(*n*) time, with the specified comparison.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mheap::{MinMaxHeap, ordering::Natural};
+    ///
+    /// let heap = MinMaxHeap::from_vec_with_cmp(vec![(1, 'b'), (3, 'a'), (1, 'a')], Natural.reverse());
+    /// assert_eq!(heap.peek_min(), Some(&(3, 'a')));
+    /// assert_eq!(heap.peek_max(), Some(&(1, 'a')));
+    /// ```
+    pub fn from_vec_with_cmp(vec: Vec<T>, cmp: C) -> Self {
+        let mut data = vec;
+        min_max::rebuild(data.as_mut_slice(), &cmp);
+        Self { data, cmp }
+    }
+
+    /// Returns the smallest element, or `None` if the heap is empty.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(1)
+    pub fn peek_min(&self) -> Option<&T> {
+        self.data.first()
+    }
+
+    /// Returns the largest element, or `None` if the heap is empty.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(1)
+    pub fn peek_max(&self) -> Option<&T> {
+        let pos = min_max::max_pos(self.data.as_slice(), &self.cmp)?;
+        Some(&self.data[pos])
+    }
+
+    /// Returns a mutable reference to the smallest element, or `None` if the heap is empty.
+    ///
+    /// The heap is restored, when the [`PeekMut`] wrapper is dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mheap::MinMaxHeap;
+    ///
+    /// let mut heap = MinMaxHeap::<_>::from([1, 5, 3]);
+    /// if let Some(mut min) = heap.peek_min_mut() {
+    ///     *min = 10;
+    /// }
+    /// assert_eq!(heap.peek_min(), Some(&3));
+    /// assert_eq!(heap.peek_max(), Some(&10));
+    /// ```
+    ///
+    /// # Time complexity
+    ///
+    /// If the item is modified then the worst case time complexity is *O*(log(*n*)),
+    /// otherwise it's *O*(1).
+    pub fn peek_min_mut(&mut self) -> Option<PeekMut<'_, T, C>> {
+        if self.data.is_empty() {
+            return None;
+        }
+        Some(PeekMut::new(self, 0))
+    }
+
+    /// Returns a mutable reference to the largest element, or `None` if the heap is empty.
+    ///
+    /// The heap is restored, when the [`PeekMut`] wrapper is dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mheap::MinMaxHeap;
+    ///
+    /// let mut heap = MinMaxHeap::<_>::from([1, 5, 3]);
+    /// if let Some(mut max) = heap.peek_max_mut() {
+    ///     *max = 0;
+    /// }
+    /// assert_eq!(heap.peek_min(), Some(&0));
+    /// assert_eq!(heap.peek_max(), Some(&3));
+    /// ```
+    ///
+    /// # Time complexity
+    ///
+    /// If the item is modified then the worst case time complexity is *O*(log(*n*)),
+    /// otherwise it's *O*(1).
+    pub fn peek_max_mut(&mut self) -> Option<PeekMut<'_, T, C>> {
+        let pos = min_max::max_pos(self.data.as_slice(), &self.cmp)?;
+        Some(PeekMut::new(self, pos))
+    }
+
+    /// Pushes an item onto the heap.
+    ///
+    /// # Time complexity
+    ///
+    /// The expected cost of `push` is *O*(1), and the worst case is *O*(log(*n*)).
+    pub fn push(&mut self, item: T) {
+        let pos = self.data.len();
+        self.data.push(item);
+        min_max::fixup(self.data.as_mut_slice(), &self.cmp, pos);
+    }
+
+    /// Removes the smallest element from the heap and returns it, or `None` if it is empty.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(log(*n*))
+    pub fn pop_min(&mut self) -> Option<T> {
+        self.peek_min_mut().map(PeekMut::pop)
+    }
+
+    /// Removes the largest element from the heap and returns it, or `None` if it is empty.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(log(*n*))
+    pub fn pop_max(&mut self) -> Option<T> {
+        self.peek_max_mut().map(PeekMut::pop)
+    }
+
+    /// Consumes the heap and returns a vector in ascending order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mheap::MinMaxHeap;
+    ///
+    /// let heap = MinMaxHeap::<_>::from([4, 1, 5, 3]);
+    /// assert_eq!(heap.into_sorted_vec(), [1, 3, 4, 5]);
+    /// ```
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*n* log(*n*))
+    pub fn into_sorted_vec(mut self) -> Vec<T> {
+        let mut sorted = Vec::with_capacity(self.len());
+        while let Some(item) = self.pop_min() {
+            sorted.push(item);
+        }
+        sorted
+    }
+
+    fn remove_at(&mut self, pos: Position) -> T {
+        let item = self.data.swap_remove(pos);
+        // In case it was the last element, we don't need to fix its position
+        if pos < self.data.len() {
+            min_max::fixup(self.data.as_mut_slice(), &self.cmp, pos);
+        }
+        item
+    }
+}
+
+impl<T, C: Default> Default for MinMaxHeap<T, C> {
+    /// Creates an empty heap.
+    fn default() -> Self {
+        Self::with_cmp(C::default())
+    }
+}
+
+impl<T, C: Cmp<T> + Default> From<Vec<T>> for MinMaxHeap<T, C> {
+    /// Creates a heap from a vector in *O*(*n*) time.
+    fn from(vec: Vec<T>) -> Self {
+        Self::from_vec_with_cmp(vec, C::default())
+    }
+}
+
+impl<T, C: Cmp<T> + Default, const N: usize> From<[T; N]> for MinMaxHeap<T, C> {
+    fn from(arr: [T; N]) -> Self {
+        Self::from(Vec::from(arr))
+    }
+}
+
+impl<T, C: Cmp<T> + Default> FromIterator<T> for MinMaxHeap<T, C> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Self::from(iter.into_iter().collect::<Vec<_>>())
+    }
+}
+
+impl<T, C: Cmp<T>> Extend<T> for MinMaxHeap<T, C> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for item in iter {
+            self.push(item);
+        }
+    }
+}
+
+impl<'a, T, C> IntoIterator for &'a MinMaxHeap<T, C> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// An iterator over the elements of a [`MinMaxHeap`], in arbitrary order.
+///
+/// This `struct` is created by [`MinMaxHeap::iter`].
+pub struct Iter<'a, T> {
+    iter: slice::Iter<'a, T>,
+}
+
+impl<T> Clone for Iter<'_, T> {
+    fn clone(&self) -> Self {
+        Self {
+            iter: self.iter.clone(),
+        }
+    }
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<T> DoubleEndedIterator for Iter<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.iter.next_back()
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+
+impl<T> FusedIterator for Iter<'_, T> {}
+
+/// Structure wrapping a mutable reference to the smallest or the largest item on a [`MinMaxHeap`].
+///
+/// This `struct` is created by the [`peek_min_mut`] and [`peek_max_mut`] methods on [`MinMaxHeap`].
+/// See their documentation for more.
+///
+/// [`peek_min_mut`]: MinMaxHeap::peek_min_mut
+/// [`peek_max_mut`]: MinMaxHeap::peek_max_mut
+pub struct PeekMut<'a, T, C: Cmp<T>> {
+    heap: &'a mut MinMaxHeap<T, C>,
+    // Invariant: pos is in bounds
+    pos: Position,
+    sift: bool,
+}
+
+impl<'a, T, C: Cmp<T>> PeekMut<'a, T, C> {
+    fn new(heap: &'a mut MinMaxHeap<T, C>, pos: Position) -> Self {
+        assert!(pos < heap.len());
+        Self {
+            heap,
+            pos,
+            sift: false,
+        }
+    }
+
+    /// Removes the peeked value from the heap and returns it.
+    pub fn pop(mut self) -> T {
+        // We don't care if the element was mutated, as we will remove it in the next line
+        self.sift = false;
+        self.heap.remove_at(self.pos)
+    }
+}
+
+impl<T, C: Cmp<T>> Drop for PeekMut<'_, T, C> {
+    fn drop(&mut self) {
+        if self.sift {
+            min_max::fixup(self.heap.data.as_mut_slice(), &self.heap.cmp, self.pos);
+        }
+    }
+}
+
+impl<T, C: Cmp<T>> Deref for PeekMut<'_, T, C> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.heap.data[self.pos]
+    }
+}
+
+impl<T, C: Cmp<T>> DerefMut for PeekMut<'_, T, C> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.sift = true;
+        &mut self.heap.data[self.pos]
+    }
+}
@@ -13,10 +13,14 @@
 //! 
 //! See [`MaxHeap`] and [`MinHeap`] for details.
 //! 
+//! Comparisons can be composed with combinators, like [`ByKey::then_by_key`] or [`ByKey::reverse`],
+//! and then passed to [`MaxHeap::with_cmp`] or [`MinHeap::with_cmp`].
+//! The composed comparison is a plain type, like [`Then`] or [`Reverse`], so there is no runtime cost.
+//! 
 //! [`max heap`]: MaxHeap
 //! [`min heap`]: MinHeap
 
-use std::cmp;
+use core::cmp;
 
 use crate::ConstDefault;
 
@@ -41,10 +45,28 @@ pub trait Ordering<T> {
     }
 }
 
+/// Orders `(key, priority)` pairs by their priority only.
+///
+/// Used by the keyed heaps, whose keys are never compared.
+#[derive(Default)]
+pub(crate) struct ByPriority<O>(pub(crate) O);
+
+impl<K, P, O: Ordering<P>> Ordering<(K, P)> for ByPriority<O> {
+    fn should_sift_up(&self, elt: &(K, P), parent: &(K, P)) -> bool {
+        self.0.should_sift_up(&elt.1, &parent.1)
+    }
+    fn should_sift_down(&self, elt: &(K, P), child: &(K, P)) -> bool {
+        self.0.should_sift_down(&elt.1, &child.1)
+    }
+    fn select_upper(&self, a: &(K, P), b: &(K, P)) -> bool {
+        self.0.select_upper(&a.1, &b.1)
+    }
+}
+
 /// Creates a max heap, where larger elements are prioritized.
 ///
 /// In a max heap, the largest element is always at the top and will be returned first by `pop()`.
-/// This is the default behavior of [`std::collections::BinaryHeap`].
+/// This is the default behavior of [`alloc::collections::BinaryHeap`].
 ///
 /// # Examples
 ///
@@ -79,7 +101,7 @@ impl<C: ConstDefault> ConstDefault for MaxHeap<C> {
 /// Creates a min heap, where smaller elements are prioritized.
 ///
 /// In a min heap, the smallest element is always at the top and will be returned first by `pop()`.
-/// This is similar to using [`std::collections::BinaryHeap`] with [`std::cmp::Reverse`] wrapper.
+/// This is similar to using [`alloc::collections::BinaryHeap`] with [`core::cmp::Reverse`] wrapper.
 ///
 /// # Examples
 ///
@@ -176,6 +198,71 @@ impl MaxHeap {
     pub fn by_key<T, K: Ord, F: Fn(&T) -> K>(key: F) -> MaxHeap<ByKey<F>> {
         MaxHeap(ByKey(key))
     }
+
+    /// Creates a new `MaxHeap` that compares elements by a floating-point key, using its [`total_cmp`].
+    ///
+    /// Elements with larger keys will be prioritized.
+    /// See [`ByTotalKey`] for how the `NaN`s and signed zeros are ordered.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mheap::{VecHeap, MaxHeap};
+    ///
+    /// let mut heap = VecHeap::with_ordering(MaxHeap::by_total_key(|item: &(&str, f64)| item.1));
+    /// heap.push(("a", 2.5));
+    /// heap.push(("b", -1.0));
+    /// heap.push(("c", 7.25));
+    ///
+    /// assert_eq!(heap.pop().map(|it| it.0), Some("c"));
+    /// ```
+    ///
+    /// [`total_cmp`]: f64::total_cmp
+    pub fn by_total_key<T, K: Float, F: Fn(&T) -> K>(key: F) -> MaxHeap<ByTotalKey<F>> {
+        MaxHeap(ByTotalKey(key))
+    }
+
+    /// Creates a new `MaxHeap` that compares elements by a floating-point key, using its [`PartialOrd`] implementation.
+    ///
+    /// Elements with larger keys will be prioritized.
+    /// The `NaN` keys are handled according to the `nan` policy, see [`NanPolicy`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mheap::{VecHeap, MaxHeap, ordering::NanPolicy};
+    ///
+    /// let mut heap = VecHeap::with_ordering(MaxHeap::by_partial_key(|x: &f64| *x, NanPolicy::First));
+    /// heap.extend([1.5, f64::NAN, 3.0]);
+    ///
+    /// assert_eq!(heap.pop(), Some(3.0));
+    /// ```
+    pub fn by_partial_key<T, K: Float, F: Fn(&T) -> K>(key: F, nan: NanPolicy) -> MaxHeap<ByPartialKey<F>> {
+        MaxHeap(ByPartialKey { key, nan })
+    }
+}
+
+impl<C> MaxHeap<C> {
+    /// Creates a new `MaxHeap` from a comparison, like the ones built by the combinators of [`Natural`], [`ByCmp`] and [`ByKey`].
+    ///
+    /// Elements, that compare as larger, will be prioritized.
+    pub fn with_cmp(cmp: C) -> Self {
+        MaxHeap(cmp)
+    }
+
+    /// Breaks ties with a custom comparison function.
+    ///
+    /// See [`ByCmp::then_by`] for details.
+    pub fn then_by<T, F: Fn(&T, &T) -> cmp::Ordering>(self, cmp: F) -> MaxHeap<Then<C, ByCmp<F>>> {
+        MaxHeap(Then(self.0, ByCmp(cmp)))
+    }
+
+    /// Breaks ties by a key extraction function.
+    ///
+    /// See [`ByKey::then_by_key`] for details.
+    pub fn then_by_key<T, K: Ord, F: Fn(&T) -> K>(self, key: F) -> MaxHeap<Then<C, ByKey<F>>> {
+        MaxHeap(Then(self.0, ByKey(key)))
+    }
 }
 
 impl MinHeap {
@@ -243,6 +330,71 @@ impl MinHeap {
     pub fn by_key<T, K: Ord, F: Fn(&T) -> K>(key: F) -> MinHeap<ByKey<F>> {
         MinHeap(ByKey(key))
     }
+
+    /// Creates a new `MinHeap` that compares elements by a floating-point key, using its [`total_cmp`].
+    ///
+    /// Elements with smaller keys will be prioritized.
+    /// See [`ByTotalKey`] for how the `NaN`s and signed zeros are ordered.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mheap::{VecHeap, MinHeap};
+    ///
+    /// let mut heap = VecHeap::with_ordering(MinHeap::by_total_key(|item: &(&str, f64)| item.1));
+    /// heap.push(("a", 2.5));
+    /// heap.push(("b", -1.0));
+    /// heap.push(("c", 7.25));
+    ///
+    /// assert_eq!(heap.pop().map(|it| it.0), Some("b"));
+    /// ```
+    ///
+    /// [`total_cmp`]: f64::total_cmp
+    pub fn by_total_key<T, K: Float, F: Fn(&T) -> K>(key: F) -> MinHeap<ByTotalKey<F>> {
+        MinHeap(ByTotalKey(key))
+    }
+
+    /// Creates a new `MinHeap` that compares elements by a floating-point key, using its [`PartialOrd`] implementation.
+    ///
+    /// Elements with smaller keys will be prioritized.
+    /// The `NaN` keys are handled according to the `nan` policy, see [`NanPolicy`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mheap::{VecHeap, MinHeap, ordering::NanPolicy};
+    ///
+    /// let mut heap = VecHeap::with_ordering(MinHeap::by_partial_key(|x: &f64| *x, NanPolicy::Last));
+    /// heap.extend([1.5, f64::NAN, 3.0]);
+    ///
+    /// assert_eq!(heap.pop(), Some(1.5));
+    /// ```
+    pub fn by_partial_key<T, K: Float, F: Fn(&T) -> K>(key: F, nan: NanPolicy) -> MinHeap<ByPartialKey<F>> {
+        MinHeap(ByPartialKey { key, nan })
+    }
+}
+
+impl<C> MinHeap<C> {
+    /// Creates a new `MinHeap` from a comparison, like the ones built by the combinators of [`Natural`], [`ByCmp`] and [`ByKey`].
+    ///
+    /// Elements, that compare as smaller, will be prioritized.
+    pub fn with_cmp(cmp: C) -> Self {
+        MinHeap(cmp)
+    }
+
+    /// Breaks ties with a custom comparison function.
+    ///
+    /// See [`ByCmp::then_by`] for details.
+    pub fn then_by<T, F: Fn(&T, &T) -> cmp::Ordering>(self, cmp: F) -> MinHeap<Then<C, ByCmp<F>>> {
+        MinHeap(Then(self.0, ByCmp(cmp)))
+    }
+
+    /// Breaks ties by a key extraction function.
+    ///
+    /// See [`ByKey::then_by_key`] for details.
+    pub fn then_by_key<T, K: Ord, F: Fn(&T) -> K>(self, key: F) -> MinHeap<Then<C, ByKey<F>>> {
+        MinHeap(Then(self.0, ByKey(key)))
+    }
 }
 
 /// Private. Do not use nor implement this trait.
@@ -273,7 +425,7 @@ impl ConstDefault for Natural {
 ///
 /// It's useful when you need custom comparison logic that doesn't fit
 /// the key extraction pattern (see [`ByKey`]).
-pub struct ByCmp<F>(F);
+pub struct ByCmp<F>(pub(crate) F);
 
 impl<T, F: Fn(&T, &T) -> cmp::Ordering> Cmp<T> for ByCmp<F> {
     fn cmp(&self, a: &T, b: &T) -> cmp::Ordering {
@@ -284,10 +436,243 @@ impl<T, F: Fn(&T, &T) -> cmp::Ordering> Cmp<T> for ByCmp<F> {
 /// A comparison implementation that compares elements by an extracted key.
 ///
 /// Use it via [`MaxHeap::by_key`] and [`MinHeap::by_key`]
-pub struct ByKey<F>(F);
+pub struct ByKey<F>(pub(crate) F);
 
 impl<T, F: Fn(&T) -> K, K: Ord> Cmp<T> for ByKey<F> {
     fn cmp(&self, a: &T, b: &T) -> cmp::Ordering {
         self.0(a).cmp(&self.0(b))
     }
 }
+
+/// A floating-point key, i.e. [`f32`] or [`f64`].
+///
+/// It is used as a bound for [`ByTotalKey`] and [`ByPartialKey`].
+pub trait Float: Copy + PartialOrd {
+    /// Compares two values with the IEEE 754 `totalOrder` predicate, like [`f64::total_cmp`].
+    fn total_cmp(&self, other: &Self) -> cmp::Ordering;
+    /// Returns `true`, if the value is a `NaN`.
+    fn is_nan(self) -> bool;
+}
+
+impl Float for f32 {
+    fn total_cmp(&self, other: &Self) -> cmp::Ordering {
+        f32::total_cmp(self, other)
+    }
+    fn is_nan(self) -> bool {
+        f32::is_nan(self)
+    }
+}
+
+impl Float for f64 {
+    fn total_cmp(&self, other: &Self) -> cmp::Ordering {
+        f64::total_cmp(self, other)
+    }
+    fn is_nan(self) -> bool {
+        f64::is_nan(self)
+    }
+}
+
+/// A comparison implementation that compares elements by an extracted floating-point key,
+/// using its [`total_cmp`].
+///
+/// The order is total, so it is safe to use with any keys, but it is stricter than the usual `<`:
+/// `-0.0` is less than `+0.0`, the positive `NaN`s are greater than infinity,
+/// and the negative `NaN`s are less than negative infinity.
+///
+/// Use it via [`MaxHeap::by_total_key`] and [`MinHeap::by_total_key`].
+///
+/// [`total_cmp`]: f64::total_cmp
+pub struct ByTotalKey<F>(pub(crate) F);
+
+impl<T, F: Fn(&T) -> K, K: Float> Cmp<T> for ByTotalKey<F> {
+    fn cmp(&self, a: &T, b: &T) -> cmp::Ordering {
+        self.0(a).total_cmp(&self.0(b))
+    }
+}
+
+/// How [`ByPartialKey`] orders the `NaN` keys.
+///
+/// The `NaN`s are equal to each other, regardless of the policy.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NanPolicy {
+    /// A `NaN` is less than any number.
+    ///
+    /// So a [`MinHeap`] pops the `NaN`s first, and a [`MaxHeap`] pops them last.
+    First,
+    /// A `NaN` is greater than any number.
+    ///
+    /// So a [`MinHeap`] pops the `NaN`s last, and a [`MaxHeap`] pops them first.
+    Last,
+    /// Comparing a `NaN` is a bug. It panics in debug builds.
+    ///
+    /// In release builds a `NaN` is greater than any number, like with [`NanPolicy::Last`].
+    #[default]
+    Panic,
+}
+
+/// A comparison implementation that compares elements by an extracted floating-point key,
+/// using its [`PartialOrd`] implementation.
+///
+/// Unlike [`ByTotalKey`], `-0.0` and `+0.0` are equal.
+/// The `NaN`s are ordered according to a [`NanPolicy`].
+///
+/// Use it via [`MaxHeap::by_partial_key`] and [`MinHeap::by_partial_key`].
+pub struct ByPartialKey<F> {
+    pub(crate) key: F,
+    pub(crate) nan: NanPolicy,
+}
+
+impl<T, F: Fn(&T) -> K, K: Float> Cmp<T> for ByPartialKey<F> {
+    fn cmp(&self, a: &T, b: &T) -> cmp::Ordering {
+        let (a, b) = ((self.key)(a), (self.key)(b));
+        if let Some(ord) = a.partial_cmp(&b) {
+            return ord;
+        }
+        debug_assert!(self.nan != NanPolicy::Panic, "compared a NaN key");
+        let ord = match (a.is_nan(), b.is_nan()) {
+            (true, true) => cmp::Ordering::Equal,
+            (true, false) => cmp::Ordering::Greater,
+            (false, true) => cmp::Ordering::Less,
+            // Only the NaNs are unordered
+            (false, false) => unreachable!(),
+        };
+        if self.nan == NanPolicy::First { ord.reverse() } else { ord }
+    }
+}
+
+/// A comparison, that reverses another comparison.
+///
+/// Use it via the `reverse` combinator, e.g. [`ByKey::reverse`].
+#[derive(Default, Clone, Copy)]
+pub struct Reverse<C>(pub(crate) C);
+
+impl<T, C: Cmp<T>> Cmp<T> for Reverse<C> {
+    fn cmp(&self, a: &T, b: &T) -> cmp::Ordering {
+        self.0.cmp(b, a)
+    }
+}
+
+impl<C: ConstDefault> ConstDefault for Reverse<C> {
+    const DEFAULT: Self = Reverse(C::DEFAULT);
+}
+
+/// A comparison, that compares by the first comparison, and breaks ties with the second one.
+///
+/// Use it via the `then` combinators, e.g. [`ByKey::then_by_key`].
+#[derive(Default, Clone, Copy)]
+pub struct Then<C1, C2>(pub(crate) C1, pub(crate) C2);
+
+impl<T, C1: Cmp<T>, C2: Cmp<T>> Cmp<T> for Then<C1, C2> {
+    fn cmp(&self, a: &T, b: &T) -> cmp::Ordering {
+        self.0.cmp(a, b).then_with(|| self.1.cmp(a, b))
+    }
+}
+
+impl<C1: ConstDefault, C2: ConstDefault> ConstDefault for Then<C1, C2> {
+    const DEFAULT: Self = Then(C1::DEFAULT, C2::DEFAULT);
+}
+
+impl<F> ByCmp<F> {
+    /// Creates a comparison from a custom function.
+    pub fn new<T>(cmp: F) -> Self
+    where
+        F: Fn(&T, &T) -> cmp::Ordering,
+    {
+        ByCmp(cmp)
+    }
+}
+
+impl<F> ByKey<F> {
+    /// Creates a comparison by a key extraction function.
+    pub fn new<T, K: Ord>(key: F) -> Self
+    where
+        F: Fn(&T) -> K,
+    {
+        ByKey(key)
+    }
+}
+
+impl<F> ByTotalKey<F> {
+    /// Creates a comparison by a floating-point key extraction function, see [`ByTotalKey`].
+    pub fn new<T, K: Float>(key: F) -> Self
+    where
+        F: Fn(&T) -> K,
+    {
+        ByTotalKey(key)
+    }
+}
+
+impl<F> ByPartialKey<F> {
+    /// Creates a comparison by a floating-point key extraction function, see [`ByPartialKey`].
+    pub fn new<T, K: Float>(key: F, nan: NanPolicy) -> Self
+    where
+        F: Fn(&T) -> K,
+    {
+        ByPartialKey { key, nan }
+    }
+}
+
+/// Implements the combinators for a comparison type.
+///
+/// Every combinator wraps `self` into a new type, so the composition has no runtime cost.
+macro_rules! impl_combinators {
+    ($(impl[$($params:tt)*] $ty:ty;)*) => {$(
+        impl<$($params)*> $ty {
+            /// Reverses the comparison.
+            ///
+            /// # Examples
+            ///
+            /// ```
+            /// use mheap::{VecHeap, MinHeap, ordering::Natural};
+            ///
+            /// let mut heap = VecHeap::with_ordering(MinHeap::with_cmp(Natural.reverse()));
+            /// heap.extend([3, 1, 5]);
+            /// assert_eq!(heap.pop(), Some(5));
+            /// ```
+            pub fn reverse(self) -> Reverse<Self> {
+                Reverse(self)
+            }
+
+            /// Breaks ties with another comparison.
+            pub fn then<C>(self, other: C) -> Then<Self, C> {
+                Then(self, other)
+            }
+
+            /// Breaks ties with a custom comparison function.
+            pub fn then_by<T, G: Fn(&T, &T) -> cmp::Ordering>(self, cmp: G) -> Then<Self, ByCmp<G>> {
+                Then(self, ByCmp(cmp))
+            }
+
+            /// Breaks ties by a key extraction function.
+            ///
+            /// # Examples
+            ///
+            /// ```
+            /// use mheap::{VecHeap, MinHeap, ordering::ByKey};
+            /// use std::cmp::Reverse;
+            ///
+            /// // Earliest deadline first, then the highest priority
+            /// let cmp = ByKey::new(|task: &(u32, u32)| task.0).then_by_key(|task: &(u32, u32)| Reverse(task.1));
+            /// let mut heap = VecHeap::with_ordering(MinHeap::with_cmp(cmp));
+            /// heap.extend([(2, 1), (1, 1), (2, 7), (1, 3)]);
+            ///
+            /// assert_eq!(heap.pop(), Some((1, 3)));
+            /// assert_eq!(heap.pop(), Some((1, 1)));
+            /// assert_eq!(heap.pop(), Some((2, 7)));
+            /// ```
+            pub fn then_by_key<T, K: Ord, G: Fn(&T) -> K>(self, key: G) -> Then<Self, ByKey<G>> {
+                Then(self, ByKey(key))
+            }
+        }
+    )*};
+}
+
+impl_combinators! {
+    impl[] Natural;
+    impl[F] ByCmp<F>;
+    impl[F] ByKey<F>;
+    impl[F] ByTotalKey<F>;
+    impl[F] ByPartialKey<F>;
+    impl[A] Reverse<A>;
+    impl[A, B] Then<A, B>;
+}
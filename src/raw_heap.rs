@@ -1,5 +1,21 @@
+//! Heap algorithms, that work over any [`Storage`].
+//!
+//! See [`RawHeap`] for details.
+
+use alloc::vec::Vec;
+
 use crate::{sift, storage::Storage, tree, ordering::Ordering, Position};
 
+/// Heap algorithms, that work over any [`Storage`].
+///
+/// This trait is implemented for every storage, and is the building block for all heaps of this crate.
+/// It does not store the [`Ordering`], so each method receives it as a parameter.
+/// Passing different orderings to the methods of the same storage is a logic error.
+/// It never causes undefined behaviour, but the heap might return elements in an arbitrary order.
+///
+/// All positions passed to these methods must be in bounds, otherwise the methods panic.
+///
+/// See [`Storage`] for an example of a custom storage.
 pub trait RawHeap: Storage {
     /// Take an element at `pos` and move it up the heap.
     ///
@@ -33,32 +49,80 @@ pub trait RawHeap: Storage {
         self.sift_up(pos, ord)
     }
 
+    /// Returns the top element, or `None` if the heap is empty.
     fn peek(&self) -> Option<&Self::Item> {
         Some(self.get(tree::root(self)?))
     }
 
+    /// Returns a guard, that gives mutable access to the top element, or `None` if the heap is empty.
+    ///
+    /// The guard does not know the ordering, so it must be restored with [`PeekMut::restore`]
+    /// after a mutation. See [`PeekMut`] for details.
     fn peek_mut(&mut self) -> Option<PeekMut<'_, Self>> {
         PeekMut::new(self)
     }
 
+    /// Replaces the top element with `last_item`, and restores the heap invariant.
+    ///
+    /// Returns the old top element. If the heap is empty, returns `last_item` back.
+    ///
+    /// This is the second half of a `pop`: the caller removes the last element of the storage,
+    /// and passes it here.
     fn pop_swap(
         &mut self,
         mut last_item: Self::Item,
         ord: &impl Ordering<Self::Key>,
     ) -> Self::Item {
         if let Some(pos) = tree::root(self) {
-            std::mem::swap(self.get_mut(pos), &mut last_item);
+            core::mem::swap(self.get_mut(pos), &mut last_item);
             self.fixup_sift_to_bottom(pos, ord);
         }
         last_item
     }
 
+    /// Restores the heap invariant for all elements, in *O*(*n*) time.
     fn rebuild(&mut self, ord: &impl Ordering<Self::Key>) {
         for i in tree::rebuild_range(self).rev() {
             self.sift_down(i, ord);
         }
     }
 
+    /// Restore heap invariant after elements at `dirty` positions were modified in place.
+    ///
+    /// The `dirty` vector is used as scratch space and is left empty.
+    fn rebuild_dirty(&mut self, dirty: &mut Vec<Position>, ord: &impl Ordering<Self::Key>) {
+        dirty.sort_unstable_by(|a, b| b.cmp(a));
+        dirty.dedup();
+        if dirty.is_empty() {
+            return;
+        }
+        if tree::better_to_rebuild(self, self.len() - dirty.len()) {
+            dirty.clear();
+            self.rebuild(ord);
+            return;
+        }
+
+        // A node has to be sifted down only if its subtree contains a dirty node.
+        // For any other node `sift_down` is a no-op, so sifting down
+        // only the dirty nodes and their ancestors bottom-up is equivalent to `rebuild`.
+        for i in 0..dirty.len() {
+            let mut pos = dirty[i];
+            while let Some(parent) = tree::parent(self, pos) {
+                dirty.push(parent);
+                pos = parent;
+            }
+        }
+        dirty.sort_unstable_by(|a, b| b.cmp(a));
+        dirty.dedup();
+        for pos in dirty.drain(..) {
+            self.sift_down(pos, ord);
+        }
+    }
+
+    /// Restores the heap invariant, when only the elements starting at `start` violate it.
+    ///
+    /// Either sifts up the tail elements one by one, or rebuilds the whole heap,
+    /// whichever is expected to be cheaper.
     fn rebuild_tail(&mut self, start: Position, ord: &impl Ordering<Self::Key>) {
         if start == self.len() {
             return;
@@ -76,11 +140,41 @@ pub trait RawHeap: Storage {
 
 impl<S: Storage + ?Sized> RawHeap for S {}
 
+/// Restores the heap invariant for the elements starting at `rebuild_from`, when dropped.
+///
+/// Elements before `rebuild_from` must be left untouched.
+/// Used to keep the heap valid even if the code in between panics.
+pub(crate) struct RebuildOnDrop<'a, S: RawHeap + ?Sized, O: Ordering<S::Key>> {
+    pub(crate) data: &'a mut S,
+    ord: &'a O,
+    pub(crate) rebuild_from: Position,
+}
+
+impl<'a, S: RawHeap + ?Sized, O: Ordering<S::Key>> RebuildOnDrop<'a, S, O> {
+    /// Starts with nothing to rebuild, so only elements added later are restored.
+    pub(crate) fn new(data: &'a mut S, ord: &'a O) -> Self {
+        let rebuild_from = data.len();
+        Self { data, ord, rebuild_from }
+    }
+}
+
+impl<S: RawHeap + ?Sized, O: Ordering<S::Key>> Drop for RebuildOnDrop<'_, S, O> {
+    fn drop(&mut self) {
+        let start = self.rebuild_from.min(self.data.len());
+        self.data.rebuild_tail(start, self.ord);
+    }
+}
+
 /// Structure wrapping a mutable reference to the top item on a [`RawHeap`].
 ///
 /// This `struct` is created by the [`peek_mut`] method on [`RawHeap`]. See
 /// its documentation for more.
 ///
+/// Unlike the `PeekMut` of the heaps in this crate, it does not restore the heap automatically.
+/// If the element was mutated via [`PeekMut::item_mut`], the owner must call [`PeekMut::restore`]
+/// (or [`PeekMut::ignore_mutation`]) before dropping it.
+/// In debug builds with the `std` feature, dropping an unrestored `PeekMut` panics.
+///
 /// [`peek_mut`]: RawHeap::peek_mut
 pub struct PeekMut<'a, S: RawHeap + ?Sized> {
     // Invariant: heap is not empty
@@ -90,10 +184,11 @@ pub struct PeekMut<'a, S: RawHeap + ?Sized> {
 
 impl<'a, S: RawHeap + ?Sized> Drop for PeekMut<'a, S> {
     fn drop(&mut self) {
-        if cfg!(debug_assertions) && self.sift {
-            if !std::thread::panicking() {
-                panic!("PeekMut must be restored before dropping");
-            }
+        // Without `std` we cannot tell if the thread is already panicking,
+        // and a second panic would abort the process
+        #[cfg(feature = "std")]
+        if cfg!(debug_assertions) && self.sift && !std::thread::panicking() {
+            panic!("PeekMut must be restored before dropping");
         }
     }
 }
@@ -112,21 +207,24 @@ impl<'a, S: RawHeap + ?Sized> PeekMut<'a, S> {
         if self.heap.is_empty() {
             // SAFETY: checked invariant in the constructor
             unsafe {
-                std::hint::unreachable_unchecked();
+                core::hint::unreachable_unchecked();
             }
         }
     }
 
+    /// The position of the top element.
     pub fn pos(&self) -> Position {
         self.assert_invariant();
         tree::root(self.heap).unwrap()
     }
 
-    pub fn as_ref(&self) -> &S::Item {
+    /// Returns the top element.
+    pub fn item(&self) -> &S::Item {
         self.heap.get(self.pos())
     }
 
-    pub fn as_mut(&mut self) -> &mut S::Item {
+    /// Returns the top element mutably, and marks the heap as needing a restore.
+    pub fn item_mut(&mut self) -> &mut S::Item {
         self.assert_invariant();
 
         // there is not need to restore the heap if it is the only element in the heap
@@ -137,6 +235,9 @@ impl<'a, S: RawHeap + ?Sized> PeekMut<'a, S> {
         self.heap.get_mut(self.pos())
     }
 
+    /// Restores the heap invariant, if the top element was mutated.
+    ///
+    /// Returns `true` if the element has moved.
     pub fn restore(&mut self, ord: &impl Ordering<S::Key>) -> bool {
         if self.sift {
             self.sift = false;
@@ -147,16 +248,26 @@ impl<'a, S: RawHeap + ?Sized> PeekMut<'a, S> {
         }
     }
 
+    /// Forgets about the mutation of the top element.
+    ///
+    /// Useful when the element is about to be removed anyway.
     pub fn ignore_mutation(&mut self) {
         self.sift = false;
     }
 
+    /// Returns the underlying heap, which might violate the heap invariant, if the top element was mutated.
     pub fn heap_incoherent(&self) -> &S {
         self.assert_invariant();
         self.heap
     }
 
-    pub fn heap_mut(&mut self) -> &mut S {
+    /// Returns the underlying heap mutably.
+    ///
+    /// Panics if the heap was not restored after a mutation.
+    ///
+    /// The heap may become empty through the returned reference,
+    /// so the caller must not use this `PeekMut` afterwards, except for dropping it.
+    pub(crate) fn heap_mut(&mut self) -> &mut S {
         self.assert_invariant();
         assert!(!self.sift);
         self.heap
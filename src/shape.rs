@@ -0,0 +1,134 @@
+//! Tree shapes of a heap.
+//!
+//! By default all heaps of this crate are binary: each node has two children.
+//! Heaps with more children per node are shallower, so `pop` touches fewer cache lines,
+//! at the price of more comparisons per level.
+//! For small keys 4-ary heaps are often faster than binary ones.
+//!
+//! See [`Shape`] for details.
+
+use core::{
+    marker::PhantomData,
+    mem::ManuallyDrop,
+    ops::{Deref, DerefMut},
+};
+
+use crate::{Position, storage::Storage};
+
+/// A tree shape of a heap, i.e. how many children each node has.
+///
+/// The shape is selected at type level, e.g. `VecHeap<T, MinHeap, DAry<4>>`.
+///
+/// # Examples
+///
+/// ```
+/// use mheap::{VecHeap, MinHeap, shape::DAry};
+///
+/// let mut heap = VecHeap::<u64, MinHeap, DAry<4>>::new();
+/// heap.extend([5, 1, 8, 3, 9, 2]);
+/// assert_eq!(heap.into_sorted_vec(), [1, 2, 3, 5, 8, 9]);
+/// ```
+pub trait Shape {
+    /// The number of children of each node. Must be at least 2.
+    const ARITY: usize;
+}
+
+/// A heap, where each node has `D` children.
+///
+/// `D` must be at least 2, otherwise using it fails to compile.
+///
+/// ```compile_fail
+/// use mheap::{VecHeap, MinHeap, shape::DAry};
+///
+/// let mut heap = VecHeap::<u64, MinHeap, DAry<1>>::new();
+/// heap.push(1);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct DAry<const D: usize>;
+
+/// The classical binary heap. This is the default shape.
+pub type Binary = DAry<2>;
+
+impl<const D: usize> Shape for DAry<D> {
+    const ARITY: usize = D;
+}
+
+/// A storage adapter, that overrides the tree shape of the underlying storage.
+pub(crate) struct Shaped<S, Sh> {
+    inner: S,
+    shape: PhantomData<Sh>,
+}
+
+impl<S, Sh> Shaped<S, Sh> {
+    pub(crate) const fn new(inner: S) -> Self {
+        Self {
+            inner,
+            shape: PhantomData,
+        }
+    }
+
+    pub(crate) fn into_inner(self) -> S {
+        self.inner
+    }
+}
+
+impl<S, Sh> Deref for Shaped<S, Sh> {
+    type Target = S;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl<S, Sh> DerefMut for Shaped<S, Sh> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.inner
+    }
+}
+
+unsafe impl<S: Storage, Sh: Shape> Storage for Shaped<S, Sh> {
+    const ARITY: usize = Sh::ARITY;
+
+    fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    type Item = S::Item;
+    type Key = S::Key;
+
+    fn key(item: &Self::Item) -> &Self::Key {
+        S::key(item)
+    }
+
+    fn get(&self, pos: Position) -> &Self::Item {
+        self.inner.get(pos)
+    }
+
+    fn get_mut(&mut self, pos: Position) -> &mut Self::Item {
+        self.inner.get_mut(pos)
+    }
+
+    fn get_key(&self, pos: Position) -> &Self::Key {
+        self.inner.get_key(pos)
+    }
+
+    type Slot = S::Slot;
+    fn slot_key(item: &Self::Slot) -> &Self::Key {
+        S::slot_key(item)
+    }
+
+    unsafe fn load(&self, pos: Position) -> ManuallyDrop<Self::Slot> {
+        // SAFETY: forwards to the underlying storage
+        unsafe { self.inner.load(pos) }
+    }
+
+    unsafe fn store(&mut self, pos: Position, item: &mut ManuallyDrop<Self::Slot>) {
+        // SAFETY: forwards to the underlying storage
+        unsafe { self.inner.store(pos, item) }
+    }
+
+    unsafe fn move_element(&mut self, src: Position, dst: Position) {
+        // SAFETY: forwards to the underlying storage
+        unsafe { self.inner.move_element(src, dst) }
+    }
+}
@@ -0,0 +1,376 @@
+//! A heap of `(key, value)` pairs, that stores the keys and the values in separate arrays.
+//!
+//! See [`SoaHeap`] for details.
+
+use alloc::vec::Vec;
+use core::{iter::FusedIterator, slice};
+
+use crate::{
+    ConstDefault, Position, RawHeap,
+    ordering::Ordering,
+    shape::{Binary, Shape, Shaped},
+    soa_vec::{PayloadSlot, SoaVec},
+    storage::Storage,
+    tree,
+};
+
+/// A heap of `(key, value)` pairs, that stores the keys and the values in separate arrays.
+///
+/// Like in a [`KeyValueHeap`], only the keys are compared.
+/// But the keys are kept in a dense array of their own, while the values stay in place.
+/// So sifting moves only the keys, together with a small payload index, and never copies the values.
+/// It pays off for large values, which a [`KeyValueHeap`] would move on every sift.
+///
+/// See [`IndexableSoaHeap`] for a variant, that allows access to elements by index.
+///
+/// The `S` generic parameter selects the tree shape, binary by default.
+/// See [`crate::shape`] for details.
+///
+/// # Examples
+///
+/// ```
+/// use mheap::{MinHeap, SoaHeap};
+///
+/// let mut heap = SoaHeap::<u64, [u8; 1024], MinHeap>::new();
+/// heap.push(30, [3; 1024]);
+/// heap.push(10, [1; 1024]);
+/// heap.push(20, [2; 1024]);
+///
+/// let (deadline, request) = heap.pop().unwrap();
+/// assert_eq!((deadline, request[0]), (10, 1));
+/// assert_eq!(heap.peek().map(|(k, v)| (*k, v[0])), Some((20, 2)));
+/// ```
+///
+/// # Time complexity
+///
+/// | Operation | Time complexity |
+/// |-----------|----------------|
+/// | `push`    | *O*(1)~        |
+/// | `pop`     | *O*(log(*n*))  |
+/// | `peek`    | *O*(1)         |
+///
+/// The value of `push` is an expected complexity.
+///
+/// [`KeyValueHeap`]: crate::KeyValueHeap
+/// [`IndexableSoaHeap`]: crate::IndexableSoaHeap
+pub struct SoaHeap<K, V, O, S = Binary> {
+    data: Shaped<SoaVec<K, V, false>, S>,
+    ord: O,
+}
+
+impl<K, V, O> SoaHeap<K, V, O> {
+    /// Creates a new empty heap with the specified ordering of the keys.
+    ///
+    /// The heap is binary. Use [`SoaHeap::with_ordering_and_shape`] for other shapes.
+    pub const fn with_ordering(ord: O) -> Self {
+        Self {
+            data: Shaped::new(SoaVec::new()),
+            ord,
+        }
+    }
+
+    /// Creates a new empty heap with the specified capacity and ordering of the keys.
+    ///
+    /// The heap will be able to hold at least `capacity` elements without reallocating.
+    pub fn with_capacity_and_ordering(capacity: usize, ord: O) -> Self {
+        Self {
+            data: Shaped::new(SoaVec::with_capacity(capacity)),
+            ord,
+        }
+    }
+
+    /// Creates a heap from a vector of `(key, value)` pairs with the specified ordering of the keys.
+    ///
+    /// The heap is built with a single bottom-up pass,
+    /// which is cheaper than pushing the pairs one by one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mheap::{MinHeap, SoaHeap};
+    ///
+    /// let mut heap = SoaHeap::from_vec_with_ordering(vec![(3, "c"), (1, "a"), (2, "b")], MinHeap::natural());
+    /// assert_eq!(heap.pop(), Some((1, "a")));
+    /// ```
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*n*)
+    pub fn from_vec_with_ordering(vec: Vec<(K, V)>, ord: O) -> Self
+    where
+        O: Ordering<K>,
+    {
+        Self::from_vec_in_shape(vec, ord)
+    }
+}
+
+impl<K, V, O, S> SoaHeap<K, V, O, S> {
+    /// Creates a new empty heap
+    pub const fn new() -> Self
+    where
+        O: ConstDefault,
+    {
+        Self {
+            data: Shaped::new(SoaVec::new()),
+            ord: O::DEFAULT,
+        }
+    }
+
+    /// Creates a new empty heap with the specified capacity
+    ///
+    /// The heap will be able to hold at least `capacity` elements without reallocating.
+    pub fn with_capacity(capacity: usize) -> Self
+    where
+        O: Default,
+    {
+        Self {
+            data: Shaped::new(SoaVec::with_capacity(capacity)),
+            ord: O::default(),
+        }
+    }
+
+    /// Creates a new empty heap with the specified ordering of the keys and tree shape.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mheap::{MinHeap, SoaHeap, shape::DAry};
+    ///
+    /// let mut heap = SoaHeap::with_ordering_and_shape(MinHeap::natural(), DAry::<4>);
+    /// heap.push(3, "c");
+    /// heap.push(1, "a");
+    /// assert_eq!(heap.pop(), Some((1, "a")));
+    /// ```
+    pub fn with_ordering_and_shape(ord: O, _shape: S) -> Self {
+        Self {
+            data: Shaped::new(SoaVec::new()),
+            ord,
+        }
+    }
+
+    fn from_vec_in_shape(vec: Vec<(K, V)>, ord: O) -> Self
+    where
+        O: Ordering<K>,
+        S: Shape,
+    {
+        let mut data = Shaped::new(SoaVec::with_capacity(vec.len()));
+        for (key, value) in vec {
+            data.push(key, value);
+        }
+        data.rebuild(&ord);
+        Self { data, ord }
+    }
+
+    /// Returns the number of elements in the heap.
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Returns `true` if the heap is empty.
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Returns the number of elements the heap can hold without reallocating.
+    pub fn capacity(&self) -> usize {
+        self.data.capacity()
+    }
+
+    /// Removes all elements from the heap.
+    pub fn clear(&mut self) {
+        self.data.clear();
+    }
+
+    /// Returns an iterator visiting all `(key, value)` pairs, in arbitrary order.
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter::new(&self.data)
+    }
+
+    /// Consumes the heap and returns the pairs in arbitrary order.
+    pub fn into_vec(self) -> Vec<(K, V)> {
+        self.data.into_inner().into_vec()
+    }
+}
+
+impl<K, V, O: Ordering<K>, S: Shape> SoaHeap<K, V, O, S> {
+    /// Returns the top pair, or `None` if the heap is empty.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(1)
+    pub fn peek(&self) -> Option<(&K, &V)> {
+        let pos = tree::root(&self.data)?;
+        Some(self.data.entry(pos))
+    }
+
+    /// Returns the top pair with a mutable value, or `None` if the heap is empty.
+    ///
+    /// The key cannot be modified, so the heap never needs to be reordered.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(1)
+    pub fn peek_mut(&mut self) -> Option<(&K, &mut V)> {
+        let pos = tree::root(&self.data)?;
+        Some(self.data.entry_mut(pos))
+    }
+
+    /// Pushes a `(key, value)` pair onto the heap.
+    ///
+    /// # Time complexity
+    ///
+    /// The expected cost of `push` is *O*(1), and the worst case is *O*(log(*n*)).
+    pub fn push(&mut self, key: K, value: V) {
+        let pos = self.data.len();
+        self.data.push(key, value);
+        self.data.sift_up(pos, &self.ord);
+    }
+
+    /// Removes the top pair from the heap and returns it, or `None` if it is empty.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(log(*n*))
+    pub fn pop(&mut self) -> Option<(K, V)> {
+        let pos = tree::root(&self.data)?;
+        Some(remove_at(&mut self.data, pos, &self.ord))
+    }
+
+    /// Consumes the heap and returns a vector of pairs in the pop order.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*n* log(*n*))
+    pub fn into_sorted_vec(mut self) -> Vec<(K, V)> {
+        let mut sorted = Vec::with_capacity(self.len());
+        while let Some(item) = self.pop() {
+            sorted.push(item);
+        }
+        sorted
+    }
+}
+
+/// Removes the element at `pos` and restores the heap invariant.
+pub(crate) fn remove_at<K, V, const TRACK: bool, S: Shape>(
+    data: &mut Shaped<SoaVec<K, V, TRACK>, S>,
+    pos: Position,
+    ord: &impl Ordering<K>,
+) -> (K, V) {
+    let item = data.swap_remove(pos);
+    // In case it was the last element, we don't need to fix its position
+    if pos < data.len() {
+        data.fixup_sift_to_bottom(pos, ord);
+    }
+    item
+}
+
+impl<K, V, O: Default, S> Default for SoaHeap<K, V, O, S> {
+    /// Creates an empty heap.
+    fn default() -> Self {
+        Self {
+            data: Shaped::new(SoaVec::new()),
+            ord: O::default(),
+        }
+    }
+}
+
+impl<K, V, O: Ordering<K> + Default, S: Shape> From<Vec<(K, V)>> for SoaHeap<K, V, O, S> {
+    /// Converts a vector of `(key, value)` pairs into a heap in *O*(*n*) time.
+    fn from(vec: Vec<(K, V)>) -> Self {
+        Self::from_vec_in_shape(vec, O::default())
+    }
+}
+
+impl<K, V, O: Ordering<K> + Default, S: Shape, const N: usize> From<[(K, V); N]> for SoaHeap<K, V, O, S> {
+    /// Converts an array of `(key, value)` pairs into a heap in *O*(*n*) time.
+    fn from(arr: [(K, V); N]) -> Self {
+        Self::from(Vec::from(arr))
+    }
+}
+
+impl<K, V, O: Ordering<K> + Default, S: Shape> FromIterator<(K, V)> for SoaHeap<K, V, O, S> {
+    /// Collects the pairs into a heap.
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        Self::from(iter.into_iter().collect::<Vec<_>>())
+    }
+}
+
+impl<K, V, O: Ordering<K>, S: Shape> Extend<(K, V)> for SoaHeap<K, V, O, S> {
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        for (key, value) in iter {
+            self.push(key, value);
+        }
+    }
+}
+
+impl<'a, K, V, O, S> IntoIterator for &'a SoaHeap<K, V, O, S> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = Iter<'a, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// An iterator over the pairs of a [`SoaHeap`] or an [`IndexableSoaHeap`], in arbitrary order.
+///
+/// This `struct` is created by [`SoaHeap::iter`] and [`IndexableSoaHeap::iter`].
+///
+/// [`IndexableSoaHeap`]: crate::IndexableSoaHeap
+/// [`IndexableSoaHeap::iter`]: crate::IndexableSoaHeap::iter
+pub struct Iter<'a, K, V> {
+    keys: slice::Iter<'a, K>,
+    slots: slice::Iter<'a, PayloadSlot>,
+    payloads: &'a [Option<V>],
+}
+
+impl<'a, K, V> Iter<'a, K, V> {
+    pub(crate) fn new<const TRACK: bool>(data: &'a SoaVec<K, V, TRACK>) -> Self {
+        let (keys, slots, payloads) = data.parts();
+        Self {
+            keys: keys.iter(),
+            slots: slots.iter(),
+            payloads,
+        }
+    }
+
+    fn payload(&self, slot: PayloadSlot) -> &'a V {
+        self.payloads[slot].as_ref().unwrap()
+    }
+}
+
+impl<K, V> Clone for Iter<'_, K, V> {
+    fn clone(&self) -> Self {
+        Self {
+            keys: self.keys.clone(),
+            slots: self.slots.clone(),
+            payloads: self.payloads,
+        }
+    }
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let key = self.keys.next()?;
+        let slot = *self.slots.next()?;
+        Some((key, self.payload(slot)))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.keys.size_hint()
+    }
+}
+
+impl<K, V> DoubleEndedIterator for Iter<'_, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let key = self.keys.next_back()?;
+        let slot = *self.slots.next_back()?;
+        Some((key, self.payload(slot)))
+    }
+}
+
+impl<K, V> ExactSizeIterator for Iter<'_, K, V> {}
+
+impl<K, V> FusedIterator for Iter<'_, K, V> {}
@@ -0,0 +1,186 @@
+use alloc::vec::Vec;
+use core::{mem::ManuallyDrop, ptr};
+
+use crate::{
+    Position,
+    indexable_vec::{Generation, Idx},
+    storage::Storage,
+};
+
+/// A place of a payload in [`SoaVec::payloads`]. It does not change, while the element is in the heap.
+pub(crate) type PayloadSlot = usize;
+
+/// A struct-of-arrays storage: the keys are kept in a dense vector, and the payloads stay in place.
+///
+/// Sifting moves only the keys and the payload slots, so a large payload is never copied.
+///
+/// If `TRACK` is set, the position of every payload is tracked,
+/// so the elements can be accessed by an [`Idx`] of their payload slot.
+pub(crate) struct SoaVec<K, V, const TRACK: bool> {
+    keys: Vec<K>,
+    /// The payload slot of the element at each position
+    slots: Vec<PayloadSlot>,
+    payloads: Vec<Option<V>>,
+    free: Vec<PayloadSlot>,
+    /// The position and generation of each payload slot. Empty unless `TRACK` is set
+    tracked: Vec<(Position, Generation)>,
+}
+
+impl<K, V, const TRACK: bool> SoaVec<K, V, TRACK> {
+    pub(crate) const fn new() -> Self {
+        Self {
+            keys: Vec::new(),
+            slots: Vec::new(),
+            payloads: Vec::new(),
+            free: Vec::new(),
+            tracked: Vec::new(),
+        }
+    }
+
+    pub(crate) fn with_capacity(capacity: usize) -> Self {
+        Self {
+            keys: Vec::with_capacity(capacity),
+            slots: Vec::with_capacity(capacity),
+            payloads: Vec::with_capacity(capacity),
+            free: Vec::new(),
+            tracked: Vec::with_capacity(if TRACK { capacity } else { 0 }),
+        }
+    }
+
+    pub(crate) fn capacity(&self) -> usize {
+        self.keys.capacity()
+    }
+
+    /// The keys and the payload slots in storage order, and the payloads by their slot
+    pub(crate) fn parts(&self) -> (&[K], &[PayloadSlot], &[Option<V>]) {
+        (&self.keys, &self.slots, &self.payloads)
+    }
+
+    pub(crate) fn entry(&self, pos: Position) -> (&K, &V) {
+        (&self.keys[pos], self.payloads[self.slots[pos]].as_ref().unwrap())
+    }
+
+    /// The key is not mutable, so the heap invariant holds
+    pub(crate) fn entry_mut(&mut self, pos: Position) -> (&K, &mut V) {
+        (&self.keys[pos], self.payloads[self.slots[pos]].as_mut().unwrap())
+    }
+
+    pub(crate) fn push(&mut self, key: K, value: V) -> Idx<V> {
+        let pos = self.keys.len();
+        let slot = match self.free.pop() {
+            Some(slot) => {
+                self.payloads[slot] = Some(value);
+                slot
+            }
+            None => {
+                self.payloads.push(Some(value));
+                if TRACK {
+                    self.tracked.push((pos, 0));
+                }
+                self.payloads.len() - 1
+            }
+        };
+        self.keys.push(key);
+        self.slots.push(slot);
+        self.record_position(pos);
+        Idx::new((slot, self.generation(slot)))
+    }
+
+    pub(crate) fn swap_remove(&mut self, pos: Position) -> (K, V) {
+        let key = self.keys.swap_remove(pos);
+        let slot = self.slots.swap_remove(pos);
+        let value = self.payloads[slot].take().unwrap();
+        self.free.push(slot);
+        if TRACK {
+            self.tracked[slot].1 = self.tracked[slot].1.wrapping_add(1);
+        }
+        // The last element was moved into the `pos`
+        if pos < self.keys.len() {
+            self.record_position(pos);
+        }
+        (key, value)
+    }
+
+    /// Removes all elements, invalidating their indices
+    pub(crate) fn clear(&mut self) {
+        while !self.keys.is_empty() {
+            self.swap_remove(self.keys.len() - 1);
+        }
+    }
+
+    /// Returns the pairs in storage order
+    pub(crate) fn into_vec(self) -> Vec<(K, V)> {
+        let Self { keys, slots, mut payloads, .. } = self;
+        keys.into_iter()
+            .zip(slots)
+            .map(|(key, slot)| (key, payloads[slot].take().unwrap()))
+            .collect()
+    }
+
+    /// Returns `None` if the index is not in use
+    pub(crate) fn try_index_to_pos(&self, index: Idx<V>) -> Option<Position> {
+        debug_assert!(TRACK, "positions are not tracked");
+        let &(pos, generation) = self.tracked.as_slice().get(index.index())?;
+        (generation == index.generation() && self.payloads[index.index()].is_some()).then_some(pos)
+    }
+
+    fn generation(&self, slot: PayloadSlot) -> Generation {
+        if TRACK { self.tracked[slot].1 } else { 0 }
+    }
+
+    fn record_position(&mut self, pos: Position) {
+        if TRACK {
+            self.tracked[self.slots[pos]].0 = pos;
+        }
+    }
+}
+
+unsafe impl<K, V, const TRACK: bool> Storage for SoaVec<K, V, TRACK> {
+    fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    type Item = K;
+
+    type Key = K;
+
+    fn key(item: &Self::Item) -> &Self::Key {
+        item
+    }
+
+    fn get(&self, pos: Position) -> &Self::Item {
+        &self.keys[pos]
+    }
+
+    fn get_mut(&mut self, pos: Position) -> &mut Self::Item {
+        &mut self.keys[pos]
+    }
+
+    type Slot = (K, PayloadSlot);
+
+    fn slot_key(item: &Self::Slot) -> &Self::Key {
+        &item.0
+    }
+
+    unsafe fn load(&self, pos: Position) -> ManuallyDrop<Self::Slot> {
+        // SAFETY: pos is not a hole, and we will never read the key from the hole
+        let key = unsafe { ptr::read(&self.keys[pos]) };
+        ManuallyDrop::new((key, self.slots[pos]))
+    }
+
+    unsafe fn store(&mut self, pos: Position, item: &mut ManuallyDrop<Self::Slot>) {
+        // SAFETY: the `item` has not been dropped
+        let (key, slot) = unsafe { ManuallyDrop::take(item) };
+        // SAFETY: pos is a hole
+        unsafe { ptr::write(&mut self.keys[pos], key) };
+        self.slots[pos] = slot;
+        self.record_position(pos);
+    }
+
+    unsafe fn move_element(&mut self, src: Position, dst: Position) {
+        // SAFETY: src is not a hole and dst is a hole, so they are different
+        unsafe { ptr::copy_nonoverlapping(&self.keys[src], &mut self.keys[dst], 1) };
+        self.slots[dst] = self.slots[src];
+        self.record_position(dst);
+    }
+}
@@ -0,0 +1,313 @@
+//! A heap, that pops equal elements in the insertion order.
+//!
+//! See [`StableHeap`] for details.
+
+use alloc::vec::Vec;
+use core::{
+    iter::FusedIterator,
+    ops::{Deref, DerefMut},
+};
+
+use crate::{ConstDefault, VecHeap, ordering::Ordering, vec_heap};
+
+/// A heap, that pops equal elements in the insertion order.
+///
+/// Binary heaps are not stable: elements of equal priority are popped in an arbitrary order.
+/// A `StableHeap` stamps each pushed element with an increasing sequence number,
+/// and breaks ties by it with the [`Stable`] ordering. So equal elements are popped first in, first out.
+///
+/// The stamps are internal: all methods accept and return plain `T`.
+///
+/// # Examples
+///
+/// ```
+/// use mheap::{MinHeap, StableHeap};
+///
+/// let mut jobs = StableHeap::with_ordering(MinHeap::by_key(|job: &(u32, &str)| job.0));
+/// jobs.push((2, "a"));
+/// jobs.push((1, "b"));
+/// jobs.push((2, "c"));
+/// jobs.push((1, "d"));
+///
+/// assert_eq!(jobs.pop(), Some((1, "b")));
+/// assert_eq!(jobs.pop(), Some((1, "d")));
+/// assert_eq!(jobs.pop(), Some((2, "a")));
+/// assert_eq!(jobs.pop(), Some((2, "c")));
+/// ```
+///
+/// # Time complexity
+///
+/// The same as of [`VecHeap`].
+pub struct StableHeap<T, O> {
+    heap: VecHeap<Stamped<T>, Stable<O>>,
+    next_seq: u64,
+}
+
+/// An element of a [`StableHeap`], stamped with its sequence number.
+///
+/// It is public only to allow its usage in trait bounds, see [`Stable`].
+pub struct Stamped<T> {
+    pub(crate) item: T,
+    pub(crate) seq: u64,
+}
+
+/// Makes an ordering stable, so equal elements are popped in the insertion order.
+///
+/// It orders [`Stamped`] elements: first by the wrapped ordering, then by the sequence number.
+/// Use it via [`StableHeap`], which stamps the elements on push.
+#[derive(Default, Clone, Copy)]
+pub struct Stable<O>(O);
+
+impl<T, O: Ordering<T>> Ordering<Stamped<T>> for Stable<O> {
+    fn should_sift_up(&self, elt: &Stamped<T>, parent: &Stamped<T>) -> bool {
+        if self.0.should_sift_up(&elt.item, &parent.item) {
+            return true;
+        }
+        !self.0.should_sift_down(&elt.item, &parent.item) && elt.seq < parent.seq
+    }
+    fn should_sift_down(&self, elt: &Stamped<T>, child: &Stamped<T>) -> bool {
+        if self.0.should_sift_down(&elt.item, &child.item) {
+            return true;
+        }
+        !self.0.should_sift_up(&elt.item, &child.item) && elt.seq > child.seq
+    }
+}
+
+impl<O: ConstDefault> ConstDefault for Stable<O> {
+    const DEFAULT: Self = Stable(O::DEFAULT);
+}
+
+impl<T, O> StableHeap<T, O> {
+    /// Creates a new empty heap
+    pub const fn new() -> Self
+    where
+        O: ConstDefault,
+    {
+        Self::with_ordering(O::DEFAULT)
+    }
+
+    /// Creates a new empty heap with the specified capacity
+    ///
+    /// The heap will be able to hold at least `capacity` elements without reallocating.
+    pub fn with_capacity(capacity: usize) -> Self
+    where
+        O: Default,
+    {
+        Self::with_capacity_and_ordering(capacity, O::default())
+    }
+
+    /// Creates a new empty heap with the specified ordering.
+    pub const fn with_ordering(ord: O) -> Self {
+        Self {
+            heap: VecHeap::with_ordering(Stable(ord)),
+            next_seq: 0,
+        }
+    }
+
+    /// Creates a new empty heap with the specified capacity and ordering.
+    ///
+    /// The heap will be able to hold at least `capacity` elements without reallocating.
+    pub fn with_capacity_and_ordering(capacity: usize, ord: O) -> Self {
+        Self {
+            heap: VecHeap::with_capacity_and_ordering(capacity, Stable(ord)),
+            next_seq: 0,
+        }
+    }
+
+    /// Returns the number of elements in the heap.
+    pub fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    /// Returns `true` if the heap is empty.
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+
+    /// Returns the number of elements the heap can hold without reallocating.
+    pub fn capacity(&self) -> usize {
+        self.heap.capacity()
+    }
+
+    /// Removes all elements from the heap.
+    pub fn clear(&mut self) {
+        self.heap.drain();
+        self.next_seq = 0;
+    }
+
+    /// Returns an iterator visiting all elements, in arbitrary order.
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter { iter: self.heap.iter() }
+    }
+
+    /// Consumes the heap and returns the elements in arbitrary order.
+    pub fn into_vec(self) -> Vec<T> {
+        self.heap.into_vec().into_iter().map(|it| it.item).collect()
+    }
+}
+
+impl<T, O: Ordering<T>> StableHeap<T, O> {
+    /// Returns the top element, or `None` if the heap is empty.
+    ///
+    /// Among equal elements, it is the one pushed first.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(1)
+    pub fn peek(&self) -> Option<&T> {
+        self.heap.peek().map(|it| &it.item)
+    }
+
+    /// Returns a mutable reference to the top element, or `None` if the heap is empty.
+    ///
+    /// The element keeps its place in the insertion order, when it is modified.
+    /// See [`VecHeap::peek_mut`] for details.
+    ///
+    /// # Time complexity
+    ///
+    /// If the element is modified, *O*(log(*n*)), otherwise *O*(1).
+    pub fn peek_mut(&mut self) -> Option<PeekMut<'_, T, O>> {
+        Some(PeekMut { peek: self.heap.peek_mut()? })
+    }
+
+    /// Pushes an element onto the heap.
+    ///
+    /// It will be popped after all equal elements, that are already in the heap.
+    ///
+    /// # Time complexity
+    ///
+    /// The expected cost of `push` is *O*(1), and the worst case is *O*(log(*n*)).
+    pub fn push(&mut self, item: T) {
+        let seq = self.next_seq;
+        self.next_seq += 1;
+        self.heap.push(Stamped { item, seq });
+    }
+
+    /// Removes the top element from the heap and returns it, or `None` if it is empty.
+    ///
+    /// Among equal elements, it is the one pushed first.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(log(*n*))
+    pub fn pop(&mut self) -> Option<T> {
+        let item = self.heap.pop()?.item;
+        if self.heap.is_empty() {
+            // No stamps are left to compare with
+            self.next_seq = 0;
+        }
+        Some(item)
+    }
+
+    /// Consumes the heap and returns a vector in the pop order.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*n* log(*n*))
+    pub fn into_sorted_vec(self) -> Vec<T> {
+        self.heap.into_sorted_vec().into_iter().map(|it| it.item).collect()
+    }
+}
+
+impl<T, O: Default> Default for StableHeap<T, O> {
+    /// Creates an empty heap.
+    fn default() -> Self {
+        Self::with_ordering(O::default())
+    }
+}
+
+impl<T, O: Ordering<T> + Default> FromIterator<T> for StableHeap<T, O> {
+    /// Pushes the elements in the iteration order.
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut heap = Self::default();
+        heap.extend(iter);
+        heap
+    }
+}
+
+impl<T, O: Ordering<T>> Extend<T> for StableHeap<T, O> {
+    /// Pushes the elements in the iteration order.
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for item in iter {
+            self.push(item);
+        }
+    }
+}
+
+impl<'a, T, O> IntoIterator for &'a StableHeap<T, O> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// An iterator over the elements of a [`StableHeap`], in arbitrary order.
+///
+/// This `struct` is created by [`StableHeap::iter`].
+pub struct Iter<'a, T> {
+    iter: vec_heap::Iter<'a, Stamped<T>>,
+}
+
+impl<T> Clone for Iter<'_, T> {
+    fn clone(&self) -> Self {
+        Self {
+            iter: self.iter.clone(),
+        }
+    }
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().map(|it| &it.item)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<T> DoubleEndedIterator for Iter<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.iter.next_back().map(|it| &it.item)
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+
+impl<T> FusedIterator for Iter<'_, T> {}
+
+/// Structure wrapping a mutable reference to the top item on a [`StableHeap`].
+///
+/// This `struct` is created by [`StableHeap::peek_mut`].
+pub struct PeekMut<'a, T, O: Ordering<T>> {
+    peek: vec_heap::PeekMut<'a, Stamped<T>, Stable<O>>,
+}
+
+impl<T, O: Ordering<T>> Deref for PeekMut<'_, T, O> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.peek.item
+    }
+}
+
+impl<T, O: Ordering<T>> DerefMut for PeekMut<'_, T, O> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.peek.item
+    }
+}
+
+impl<T, O: Ordering<T>> PeekMut<'_, T, O> {
+    /// Removes the peeked value from the heap and returns it.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(log(*n*))
+    pub fn pop(self) -> T {
+        self.peek.pop().item
+    }
+}
@@ -1,30 +1,157 @@
-use std::{mem::ManuallyDrop, ptr};
+//! The low-level interface between heap algorithms and the containers they operate on.
+//!
+//! See [`Storage`] for details.
+
+use alloc::vec::Vec;
+use core::{mem::ManuallyDrop, ptr};
 
 use crate::Position;
 
+/// A container of heap elements, addressed by [`Position`].
+///
+/// Implement this trait to get all heap algorithms of [`RawHeap`] for your container.
+/// The tree shape is implied by positions: the root is at `0`, and the children of `pos`
+/// are at `ARITY * pos + 1` up to `ARITY * pos + ARITY`. See [`Self::ARITY`].
+///
+/// Each element is split into three views:
+/// * [`Self::Item`] - what users of the storage see through [`Self::get`] and [`Self::get_mut`].
+/// * [`Self::Key`] - the part of the item, that is compared by an [`Ordering`].
+/// * [`Self::Slot`] - everything that has to travel together with the element, when it changes position.
+///   E.g. [`IndexableHeap`] keeps the element index in the slot.
+///
+/// # Holes
+///
+/// Sifting is done via holes. An element is moved out of the storage with [`Self::load`],
+/// leaving a *hole* at its position. Then other elements are moved into the hole with
+/// [`Self::move_element`], which moves the hole to their old position.
+/// Finally the loaded element is written into the hole with [`Self::store`].
+///
+/// While a storage has a hole, the heap algorithms only call [`Self::len`], [`Self::get_key`]
+/// (for positions other than the hole), and the three hole operations.
+///
 /// # Safety
 ///
-/// The [`Self::parent`] and [`Self::child`] must never return the argument.
+/// Implementors must guarantee:
+/// * [`Self::len`] does not change, unless the storage is accessed by `&mut`
+///   through methods other than the hole operations.
+/// * [`Self::get`], [`Self::get_mut`] and [`Self::get_key`] panic (rather than cause undefined behaviour)
+///   for positions outside of `0..len`, and access only the element at the given position.
+///   So they are safe to call, while there is a hole at another position.
+/// * [`Self::load`], [`Self::store`] and [`Self::move_element`] do not panic, when their safety requirements are met.
+///   A panic in the middle of sifting would leave a hole behind.
+///
+/// Users of the hole operations must guarantee:
+/// * A storage with a hole is never passed to a safe function, that might read from the hole.
+///   Unless specified otherwise, when a function receives a storage as a parameter, it does not have any holes.
+/// * Any code that creates a hole restores it, before the storage is used otherwise or dropped.
+///
+/// # Examples
+///
+/// A storage of `(priority, payload)` pairs, where only the priority is compared:
+///
+/// ```
+/// use std::{mem::ManuallyDrop, ptr};
+/// use mheap::{MaxHeap, Position, RawHeap, ordering::Ordering, storage::Storage};
+///
+/// struct Tasks(Vec<(u32, &'static str)>);
+///
+/// unsafe impl Storage for Tasks {
+///     fn len(&self) -> usize {
+///         self.0.len()
+///     }
+///
+///     type Item = (u32, &'static str);
+///     type Key = u32;
+///
+///     fn key(item: &Self::Item) -> &Self::Key {
+///         &item.0
+///     }
+///
+///     fn get(&self, pos: Position) -> &Self::Item {
+///         &self.0[pos]
+///     }
+///
+///     fn get_mut(&mut self, pos: Position) -> &mut Self::Item {
+///         &mut self.0[pos]
+///     }
+///
+///     type Slot = Self::Item;
+///
+///     fn slot_key(slot: &Self::Slot) -> &Self::Key {
+///         &slot.0
+///     }
+///
+///     unsafe fn load(&self, pos: Position) -> ManuallyDrop<Self::Slot> {
+///         // SAFETY: `pos` is not a hole, and the caller will never read the hole
+///         ManuallyDrop::new(unsafe { ptr::read(&self.0[pos]) })
+///     }
+///
+///     unsafe fn store(&mut self, pos: Position, slot: &mut ManuallyDrop<Self::Slot>) {
+///         // SAFETY: `pos` is a hole, so we must not drop its content.
+///         //         The `slot` is taken only once.
+///         unsafe { ptr::write(&mut self.0[pos], ManuallyDrop::take(slot)) };
+///     }
 ///
-/// If any code creates a hole, this storage must never be passed to any safe function, that might try to read from the hole.
-/// Unless specified otherwise, when a function receives a storage as parameter, it does not have any holes.
+///     unsafe fn move_element(&mut self, src: Position, dst: Position) {
+///         // SAFETY: `src` is not a hole and `dst` is a hole, so they are different
+///         unsafe { ptr::copy_nonoverlapping(&self.0[src], &mut self.0[dst], 1) };
+///     }
+/// }
 ///
-/// Any code that creates a hole must restore the hole before the storage is dropped.
+/// let ord = MaxHeap::natural();
+/// let mut tasks = Tasks(vec![(1, "low"), (5, "high"), (3, "medium")]);
+/// tasks.rebuild(&ord);
+/// assert_eq!(tasks.peek(), Some(&(5, "high")));
+///
+/// tasks.0.push((4, "new"));
+/// tasks.sift_up(3, &ord);
+/// let last = tasks.0.pop().unwrap();
+/// assert_eq!(tasks.pop_swap(last, &ord), (5, "high"));
+/// assert_eq!(tasks.peek(), Some(&(4, "new")));
+/// ```
+///
+/// [`RawHeap`]: crate::RawHeap
+/// [`Ordering`]: crate::ordering::Ordering
+/// [`IndexableHeap`]: crate::IndexableHeap
 pub unsafe trait Storage {
+    /// The number of children of each node. The storage is a binary heap by default.
+    ///
+    /// Must be at least 2, otherwise the heap algorithms fail to compile.
+    /// See [`Shape`] for details.
+    ///
+    /// [`Shape`]: crate::shape::Shape
+    const ARITY: usize = 2;
+
+    /// The number of elements in the storage.
     fn len(&self) -> usize;
+    /// Returns `true` if the storage has no elements.
     fn is_empty(&self) -> bool {
         self.len() == 0
     }
 
     /// The whole item that is stored
     type Item;
-    /// The key part of the item
+    /// The key part of the item, that is compared by an [`Ordering`]
+    ///
+    /// [`Ordering`]: crate::ordering::Ordering
     type Key;
 
+    /// Extracts the key part of an item.
     fn key(item: &Self::Item) -> &Self::Key;
 
+    /// Returns the item at `pos`.
+    ///
+    /// Panics if `pos` is out of bounds.
     fn get(&self, pos: Position) -> &Self::Item;
+    /// Returns the item at `pos` mutably.
+    ///
+    /// The caller is responsible to restore the heap invariant, if the key is modified.
+    ///
+    /// Panics if `pos` is out of bounds.
     fn get_mut(&mut self, pos: Position) -> &mut Self::Item;
+    /// Returns the key of the item at `pos`.
+    ///
+    /// Panics if `pos` is out of bounds.
     fn get_key(&self, pos: Position) -> &Self::Key {
         Self::key(self.get(pos))
     }
@@ -33,25 +160,30 @@ pub unsafe trait Storage {
     /// It is used to put back data into the hole,
     /// which was possibly moved to another position.
     type Slot;
+    /// Extracts the key part of a loaded slot.
+    ///
+    /// Must return the same key, as [`Self::get_key`] returned before the slot was loaded.
     fn slot_key(item: &Self::Slot) -> &Self::Key;
     /// Loads an element and creates a hole at `pos`
     ///
     /// # Safety
     ///
+    /// * `pos` must be in bounds
     /// * `pos` must not be a hole
     /// * Further "read" operations must never access the hole
     ///
     /// This operation creates a hole at `pos`
     ///
-    /// By "read" operations, we mean (to be added, when more operations are added):
-    /// * [`Self::get`]
+    /// By "read" operations, we mean:
+    /// * [`Self::get`], [`Self::get_mut`] and [`Self::get_key`]
+    /// * [`Self::load`] and the `src` of [`Self::move_element`]
     unsafe fn load(&self, pos: Position) -> ManuallyDrop<Self::Slot>;
     /// Stores an element into a hole at `pos`
     ///
     /// # Safety
     ///
     /// * `pos` must be a hole
-    /// * the `item` must not be dropped
+    /// * the `item` must not be dropped, nor used after this call
     ///
     /// This operation consumes the hole at `pos`
     unsafe fn store(&mut self, pos: Position, item: &mut ManuallyDrop<Self::Slot>);
@@ -59,6 +191,7 @@ pub unsafe trait Storage {
     ///
     /// # Safety
     ///
+    /// * `src` must be in bounds
     /// * `src` must not be a hole
     /// * `dst` must be a hole
     ///
@@ -91,17 +224,6 @@ unsafe impl<T> Storage for [T] {
         Self::key(item)
     }
 
-    /// Loads an element and creates a hole at `pos`
-    ///
-    /// # Safety
-    ///
-    /// * `pos` must not be a hole
-    /// * Further "read" operations must never access the hole
-    ///
-    /// This operation creates a hole at `pos`
-    ///
-    /// By "read" operations, we mean (to be added, when more operations are added):
-    /// * [`Self::get`]
     unsafe fn load(&self, pos: Position) -> ManuallyDrop<Self::Item> {
         // SAFETY: pos is not a hole
         //         and we will never read the data from the hole
@@ -109,14 +231,6 @@ unsafe impl<T> Storage for [T] {
         ManuallyDrop::new(data)
     }
 
-    /// Stores an element into a hole at `pos`
-    ///
-    /// # Safety
-    ///
-    /// * `pos` must be a hole
-    /// * the `item` must not be dropped
-    ///
-    /// This operation consumes the hole at `pos`
     unsafe fn store(&mut self, pos: Position, item: &mut ManuallyDrop<Self::Item>) {
         // SAFETY: the `item` has not been dropped
         let item = unsafe { ManuallyDrop::take(item) };
@@ -124,14 +238,6 @@ unsafe impl<T> Storage for [T] {
         unsafe { ptr::write(&mut self[pos], item) };
     }
 
-    /// Moves element from `src` into a hole at `dst` without dropping.
-    ///
-    /// # Safety
-    ///
-    /// * `src` must not be a hole
-    /// * `dst` must be a hole
-    ///
-    /// This operation moves the hole from `dst` to `src`
     unsafe fn move_element(&mut self, src: Position, dst: Position) {
         // SAFETY: src is not a hole and dst is a hole
         //         they cannot be equal, as the position cannot be a hole and not a hole at the same time
@@ -186,3 +292,50 @@ unsafe impl<T> Storage for Vec<T> {
         unsafe { self.as_mut_slice().move_element(src, dst) }
     }
 }
+
+unsafe impl<S: Storage + ?Sized> Storage for &mut S {
+    const ARITY: usize = S::ARITY;
+
+    fn len(&self) -> usize {
+        (**self).len()
+    }
+
+    type Item = S::Item;
+    type Key = S::Key;
+
+    fn key(item: &Self::Item) -> &Self::Key {
+        S::key(item)
+    }
+
+    fn get(&self, pos: Position) -> &Self::Item {
+        (**self).get(pos)
+    }
+
+    fn get_mut(&mut self, pos: Position) -> &mut Self::Item {
+        (**self).get_mut(pos)
+    }
+
+    fn get_key(&self, pos: Position) -> &Self::Key {
+        (**self).get_key(pos)
+    }
+
+    type Slot = S::Slot;
+    fn slot_key(item: &Self::Slot) -> &Self::Key {
+        S::slot_key(item)
+    }
+
+    unsafe fn load(&self, pos: Position) -> ManuallyDrop<Self::Slot> {
+        // SAFETY: forwards to the underlying storage
+        unsafe { (**self).load(pos) }
+    }
+
+    unsafe fn store(&mut self, pos: Position, item: &mut ManuallyDrop<Self::Slot>) {
+        // SAFETY: forwards to the underlying storage
+        unsafe { (**self).store(pos, item) }
+    }
+
+    unsafe fn move_element(&mut self, src: Position, dst: Position) {
+        // SAFETY: forwards to the underlying storage
+        unsafe { (**self).move_element(src, dst) }
+    }
+}
@@ -4,19 +4,24 @@ pub(crate) fn root<S: Storage + ?Sized>(data: &S) -> Option<Position> {
     (data.len() != 0).then_some(0)
 }
 
+/// The number of children of each node
+const fn arity<S: Storage + ?Sized>() -> usize {
+    const { assert!(S::ARITY >= 2, "a heap node must have at least 2 children") };
+    S::ARITY
+}
+
 /// Returns an item parent node
 ///
 /// It is guaranteed that the parent is different from the argument
 pub(crate) fn parent<S: Storage + ?Sized>(_data: &S, pos: Position) -> Option<Position> {
-    // SAFETY: consider `k > 0`. Then there are 3 cases:
-    // case pos = 2k + 1:
-    //    parent = (2k - 1) / 2 = k;
-    // case pos = 2k + 2:
-    //    parent = (2k + 1) / 2 = k;
+    // SAFETY: let `d = arity >= 2`. Then there are 2 cases:
+    // case pos = d * k + 1 + i, for some `k` and `0 <= i < d`:
+    //    parent = (d * k + i) / d = k;
+    //    since d * k >= k, we have pos > k
     // case pos = 0;
     //    we return None
-    // Since 2k > k, we never return the pos itself
-    Some(pos.checked_sub(1)? / 2)
+    // So we never return the pos itself
+    Some(pos.checked_sub(1)? / arity::<S>())
 }
 
 /// Returns nth child of a node
@@ -27,33 +32,24 @@ pub(crate) fn child<S: Storage + ?Sized>(
     pos: Position,
     index: usize,
 ) -> Option<Position> {
-    assert!(index < 2);
+    assert!(index < arity::<S>());
     // FIXME: this expression could overflow if T is a ZST
-    let child = 2 * pos + 1 + index;
-    // SAFETY: for any `pos` we have `2 * pos >= pos`, and `1 + index > 0`, so `child > pos`
+    let child = arity::<S>() * pos + 1 + index;
+    // SAFETY: for any `pos` we have `arity * pos >= pos`, and `1 + index > 0`, so `child > pos`
     (child < data.len()).then_some(child)
 }
 
-/// Selects a node, or its next sibling, based on the condition
-pub(crate) fn select_sibling<S: Storage + ?Sized>(
-    _data: &S,
-    pos: Position,
-    cond: bool,
-) -> Option<Position> {
-    Some(pos + (cond as usize))
-}
-
 /// Checks if a node has all children
 pub(crate) fn is_whole_node<S: Storage + ?Sized>(data: &S, pos: Position) -> bool {
-    child(data, pos, 1).is_some()
+    child(data, pos, arity::<S>() - 1).is_some()
 }
 
-/// Checks if a node has all children
+/// Returns the number of children of a node
 pub(crate) fn nchildren<S: Storage + ?Sized>(data: &S, pos: Position) -> usize {
-    let first = 2 * pos + 1;
+    let first = arity::<S>() * pos + 1;
     let len = data.len();
     let s = len.saturating_sub(first);
-    s.min(2)
+    s.min(arity::<S>())
 }
 
 pub(crate) fn children<S: Storage + ?Sized>(
@@ -64,9 +60,12 @@ pub(crate) fn children<S: Storage + ?Sized>(
     (0..n).map(move |index| child(data, pos, index).unwrap())
 }
 
-pub(crate) fn rebuild_range<S: Storage + ?Sized>(data: &S) -> std::ops::Range<Position> {
-    let len = data.len();
-    let n = len / 2;
+pub(crate) fn rebuild_range<S: Storage + ?Sized>(data: &S) -> core::ops::Range<Position> {
+    // The last node with children is the parent of the last element
+    let n = match data.len().checked_sub(1) {
+        Some(last) => parent(data, last).map_or(0, |pos| pos + 1),
+        None => 0,
+    };
     0..n
 }
 
@@ -97,6 +96,10 @@ fn log2_fast(x: usize) -> usize {
 #[cfg(test)]
 mod tests {
 
+    use alloc::vec;
+
+    use crate::shape::{DAry, Shaped};
+
     #[test]
     fn children() {
         assert_eq!(super::nchildren([0u32; 3].as_slice(), 0), 2);
@@ -105,4 +108,15 @@ mod tests {
         assert_eq!(super::nchildren([0u32; 5].as_slice(), 1), 2);
         assert_eq!(super::nchildren([0u32; 6].as_slice(), 1), 2);
     }
+
+    #[test]
+    fn dary_children() {
+        let data = Shaped::<_, DAry<4>>::new(vec![0u32; 10]);
+        assert_eq!(super::nchildren(&data, 0), 4);
+        assert_eq!(super::nchildren(&data, 1), 4);
+        assert_eq!(super::nchildren(&data, 2), 1);
+        assert_eq!(super::parent(&data, 4), Some(0));
+        assert_eq!(super::parent(&data, 5), Some(1));
+        assert_eq!(super::rebuild_range(&data), 0..3);
+    }
 }
@@ -1,16 +1,28 @@
-//! A simple heap stored in a [`Vec`]. Analogous to [`std::collections::BinaryHeap`].
+//! A simple heap stored in a [`Vec`]. Analogous to [`alloc::collections::BinaryHeap`].
 //! 
 //! See [`VecHeap`] for details.
 
-use std::ops::{Deref, DerefMut};
+use alloc::vec::{self, Vec};
+use core::{
+    iter::FusedIterator,
+    ops::{Deref, DerefMut},
+    slice,
+};
 
-use crate::{ConstDefault, ordering::Ordering, RawHeap, raw_heap};
+use crate::{
+    ConstDefault, ordering::Ordering, Position, RawHeap,
+    raw_heap::{self, RebuildOnDrop},
+    shape::{Binary, Shape, Shaped},
+};
 
-/// A simple heap stored in a [`Vec`]. Analogous to [`std::collections::BinaryHeap`].
+/// A simple heap stored in a [`Vec`]. Analogous to [`alloc::collections::BinaryHeap`].
 ///
 /// Use the `O` generic parameter to select [`MaxHeap`] or [`MinHeap`].
 /// See [`crate::ordering`] for details.
 ///
+/// The `S` generic parameter selects the tree shape, binary by default.
+/// See [`crate::shape`] for details.
+///
 /// # Examples
 ///
 /// ```
@@ -35,19 +47,67 @@ use crate::{ConstDefault, ordering::Ordering, RawHeap, raw_heap};
 ///
 /// [`MaxHeap`]: crate::MaxHeap
 /// [`MinHeap`]: crate::MinHeap
-pub struct VecHeap<T, O> {
-    data: Vec<T>,
+pub struct VecHeap<T, O, S = Binary> {
+    data: Shaped<Vec<T>, S>,
     ord: O,
 }
 
 impl<T, O> VecHeap<T, O> {
+    /// Creates a new empty heap with the specified ordering.
+    ///
+    /// The heap is binary. Use [`VecHeap::with_ordering_and_shape`] for other shapes.
+    pub const fn with_ordering(ord: O) -> Self {
+        Self {
+            data: Shaped::new(Vec::new()),
+            ord,
+        }
+    }
+
+    /// Creates a new empty heap with the specified capacity and ordering.
+    ///
+    /// The heap will be able to hold at least `capacity` elements without reallocating.
+    pub fn with_capacity_and_ordering(capacity: usize, ord: O) -> Self {
+        Self {
+            data: Shaped::new(Vec::with_capacity(capacity)),
+            ord,
+        }
+    }
+
+    /// Creates a heap from a vector with the specified ordering.
+    ///
+    /// The heap is built in place with a single bottom-up pass,
+    /// which is cheaper than pushing the elements one by one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mheap::{VecHeap, MinHeap};
+    ///
+    /// let mut heap = VecHeap::from_vec_with_ordering(vec![3, 1, 5], MinHeap::natural());
+    /// assert_eq!(heap.pop(), Some(1));
+    /// assert_eq!(heap.pop(), Some(3));
+    /// assert_eq!(heap.pop(), Some(5));
+    /// ```
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*n*)
+    pub fn from_vec_with_ordering(vec: Vec<T>, ord: O) -> Self
+    where
+        O: Ordering<T>,
+    {
+        Self::from_vec_in_shape(vec, ord)
+    }
+}
+
+impl<T, O, S> VecHeap<T, O, S> {
     /// Creates a new empty heap
     pub const fn new() -> Self
     where
         O: ConstDefault,
     {
         Self {
-            data: Vec::new(),
+            data: Shaped::new(Vec::new()),
             ord: O::DEFAULT,
         }
     }
@@ -60,27 +120,38 @@ impl<T, O> VecHeap<T, O> {
         O: Default,
     {
         Self {
-            data: Vec::with_capacity(capacity),
+            data: Shaped::new(Vec::with_capacity(capacity)),
             ord: O::default(),
         }
     }
 
-    /// Creates a new empty heap with the specified ordering.
-    pub const fn with_ordering(ord: O) -> Self {
+    /// Creates a new empty heap with the specified ordering and tree shape.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mheap::{VecHeap, MinHeap, shape::DAry};
+    ///
+    /// let mut heap = VecHeap::with_ordering_and_shape(MinHeap::by_key(|it: &(u32, &str)| it.0), DAry::<4>);
+    /// heap.push((3, "c"));
+    /// heap.push((1, "a"));
+    /// assert_eq!(heap.pop(), Some((1, "a")));
+    /// ```
+    pub fn with_ordering_and_shape(ord: O, _shape: S) -> Self {
         Self {
-            data: Vec::new(),
+            data: Shaped::new(Vec::new()),
             ord,
         }
     }
 
-    /// Creates a new empty heap with the specified capacity and ordering.
-    ///
-    /// The heap will be able to hold at least `capacity` elements without reallocating.
-    pub fn with_capacity_and_ordering(capacity: usize, ord: O) -> Self {
-        Self {
-            data: Vec::with_capacity(capacity),
-            ord,
-        }
+    fn from_vec_in_shape(vec: Vec<T>, ord: O) -> Self
+    where
+        O: Ordering<T>,
+        S: Shape,
+    {
+        let mut data = Shaped::new(vec);
+        data.rebuild(&ord);
+        Self { data, ord }
     }
 
     /// Returns the number of elements in the heap.
@@ -130,9 +201,82 @@ impl<T, O> VecHeap<T, O> {
     pub fn is_empty(&self) -> bool {
         self.data.is_empty()
     }
+
+    /// Returns an iterator visiting all values in the underlying vector, in arbitrary order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mheap::{VecHeap, MaxHeap};
+    ///
+    /// let heap = VecHeap::<_, MaxHeap>::from([1, 2, 3, 4]);
+    /// assert_eq!(heap.iter().sum::<i32>(), 10);
+    /// ```
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            iter: self.data.iter(),
+        }
+    }
+
+    /// Returns a slice of all values in the underlying vector, in arbitrary order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mheap::{VecHeap, MaxHeap};
+    ///
+    /// let heap = VecHeap::<_, MaxHeap>::from([1, 2, 3, 4]);
+    /// assert_eq!(heap.as_slice().len(), 4);
+    /// assert_eq!(heap.as_slice()[0], 4); // The top element is always first
+    /// ```
+    pub fn as_slice(&self) -> &[T] {
+        self.data.as_slice()
+    }
+
+    /// Consumes the heap and returns the underlying vector in arbitrary order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mheap::{VecHeap, MaxHeap};
+    ///
+    /// let heap = VecHeap::<_, MaxHeap>::from([1, 2, 3, 4]);
+    /// let mut vec = heap.into_vec();
+    /// vec.sort();
+    /// assert_eq!(vec, [1, 2, 3, 4]);
+    /// ```
+    pub fn into_vec(self) -> Vec<T> {
+        self.data.into_inner()
+    }
+
+    /// Clears the heap, returning an iterator over the removed elements in arbitrary order.
+    ///
+    /// If the iterator is dropped before being fully consumed,
+    /// it drops the remaining elements in arbitrary order.
+    ///
+    /// The returned iterator keeps a mutable borrow on the heap to optimize its implementation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mheap::{VecHeap, MaxHeap};
+    ///
+    /// let mut heap = VecHeap::<_, MaxHeap>::from([1, 3]);
+    /// assert!(!heap.is_empty());
+    ///
+    /// for x in heap.drain() {
+    ///     println!("{x}");
+    /// }
+    /// assert!(heap.is_empty());
+    /// ```
+    pub fn drain(&mut self) -> Drain<'_, T> {
+        Drain {
+            iter: self.data.drain(..),
+        }
+    }
 }
 
-impl<T, O: Ordering<T>> VecHeap<T, O> {
+impl<T, O: Ordering<T>, S: Shape> VecHeap<T, O, S> {
     /// Returns a reference to the top element in the heap, or `None` if it is empty.
     ///
     /// # Examples
@@ -183,7 +327,7 @@ impl<T, O: Ordering<T>> VecHeap<T, O> {
     ///
     /// If the item is modified then the worst case time complexity is *O*(log(*n*)),
     /// otherwise it's *O*(1).
-    pub fn peek_mut(&mut self) -> Option<PeekMut<'_, T, O>> {
+    pub fn peek_mut(&mut self) -> Option<PeekMut<'_, T, O, S>> {
         RawHeap::peek_mut(&mut self.data).map(|raw| PeekMut {
             raw,
             ord: &self.ord,
@@ -362,47 +506,538 @@ impl<T, O: Ordering<T>> VecHeap<T, O> {
     /// ```
     pub fn append(&mut self, other: &mut Self) {
         if self.len() < other.len() {
-            std::mem::swap(self, other);
+            core::mem::swap(self, other);
         }
 
         let start = self.len();
         self.data.append(&mut other.data);
         self.data.rebuild_tail(start, &self.ord);
     }
+
+    /// Retains only the elements specified by the predicate.
+    ///
+    /// In other words, remove all elements `e` for which `f(&e)` returns `false`.
+    /// The elements are visited in arbitrary order.
+    ///
+    /// The heap invariant is restored once, after all elements were visited.
+    /// Only the part of the heap after the first removed element is rebuilt.
+    /// If the predicate panics, the heap is still restored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mheap::{VecHeap, MaxHeap};
+    ///
+    /// let mut heap = VecHeap::<_, MaxHeap>::from([-10, -5, 1, 2, 4, 13]);
+    /// heap.retain(|x| x % 2 == 0); // only keep even numbers
+    /// assert_eq!(heap.into_sorted_vec(), [4, 2, -10]);
+    /// ```
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*n*)
+    pub fn retain<F: FnMut(&T) -> bool>(&mut self, mut f: F) {
+        let mut guard = RebuildOnDrop::new(&mut self.data, &self.ord);
+        let mut pos = 0;
+        guard.data.retain(|item| {
+            let keep = f(item);
+            if !keep && pos < guard.rebuild_from {
+                guard.rebuild_from = pos;
+            }
+            pos += 1;
+            keep
+        });
+    }
+
+    /// Retains only the elements specified by the predicate, passing a mutable reference to it.
+    ///
+    /// In other words, remove all elements `e` for which `f(&mut e)` returns `false`.
+    /// The elements are visited in arbitrary order.
+    ///
+    /// Since any element might be modified, the whole heap is rebuilt once,
+    /// after all elements were visited. If the predicate panics, the heap is still restored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mheap::{VecHeap, MinHeap};
+    ///
+    /// let mut heap = VecHeap::<_, MinHeap>::from([1, 2, 3, 4, 5]);
+    /// heap.retain_mut(|x| {
+    ///     *x *= 10;
+    ///     *x != 30
+    /// });
+    /// assert_eq!(heap.into_sorted_vec(), [10, 20, 40, 50]);
+    /// ```
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*n*)
+    pub fn retain_mut<F: FnMut(&mut T) -> bool>(&mut self, f: F) {
+        let mut guard = RebuildOnDrop::new(&mut self.data, &self.ord);
+        guard.rebuild_from = 0;
+        guard.data.retain_mut(f);
+    }
+
+    /// Creates an iterator which uses a predicate to determine if an element should be removed.
+    ///
+    /// If the predicate returns `true`, the element is removed from the heap and yielded.
+    /// Otherwise the element remains in the heap.
+    /// The elements are visited in arbitrary order.
+    ///
+    /// If the returned iterator is not exhausted, the unvisited elements remain in the heap.
+    /// The heap invariant is restored once, when the iterator is dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mheap::{VecHeap, MinHeap};
+    ///
+    /// let mut heap = VecHeap::<_, MinHeap>::from([1, 2, 3, 4, 5, 6]);
+    /// let mut evens: Vec<_> = heap.extract_if(|x| x % 2 == 0).collect();
+    /// evens.sort();
+    /// assert_eq!(evens, [2, 4, 6]);
+    /// assert_eq!(heap.into_sorted_vec(), [1, 3, 5]);
+    /// ```
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*n*) in total.
+    pub fn extract_if<F: FnMut(&T) -> bool>(&mut self, pred: F) -> ExtractIf<'_, T, O, F, S> {
+        ExtractIf {
+            guard: RebuildOnDrop::new(&mut self.data, &self.ord),
+            pos: 0,
+            pred,
+        }
+    }
+
+    /// Consumes the heap and returns a vector in sorted order.
+    ///
+    /// The elements are sorted in the order they would have been popped,
+    /// i.e. the top element of the heap comes first.
+    /// So a [`MinHeap`] produces an ascending vector,
+    /// and a [`MaxHeap`] produces a descending one.
+    ///
+    /// Note that this differs from [`alloc::collections::BinaryHeap::into_sorted_vec`],
+    /// which always returns the elements in ascending order.
+    ///
+    /// The sorting is done in place, without any additional allocations.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mheap::{VecHeap, MaxHeap, MinHeap};
+    ///
+    /// let heap = VecHeap::<_, MinHeap>::from([4, 1, 3, 2]);
+    /// assert_eq!(heap.into_sorted_vec(), [1, 2, 3, 4]);
+    ///
+    /// let heap = VecHeap::<_, MaxHeap>::from([4, 1, 3, 2]);
+    /// assert_eq!(heap.into_sorted_vec(), [4, 3, 2, 1]);
+    /// ```
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*n* log(*n*))
+    ///
+    /// [`MaxHeap`]: crate::MaxHeap
+    /// [`MinHeap`]: crate::MinHeap
+    pub fn into_sorted_vec(mut self) -> Vec<T> {
+        // Classic heapsort: move the top element past the end of the heap,
+        // then shrink the heap by one. The tail accumulates elements in reverse pop order.
+        let mut end = self.data.len();
+        while end > 1 {
+            end -= 1;
+            self.data.swap(0, end);
+            Shaped::<_, S>::new(&mut self.data[..end]).fixup_sift_to_bottom(0, &self.ord);
+        }
+        self.data.reverse();
+        self.data.into_inner()
+    }
+
+    /// Returns an iterator which retrieves elements in heap order.
+    ///
+    /// The elements are produced lazily, as if by calling [`VecHeap::pop`] repeatedly.
+    /// This consumes the heap.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mheap::{VecHeap, MaxHeap};
+    ///
+    /// let heap = VecHeap::<_, MaxHeap>::from([1, 2, 3, 4, 5]);
+    /// assert_eq!(heap.into_iter_sorted().take(2).collect::<Vec<_>>(), [5, 4]);
+    /// ```
+    ///
+    /// # Time complexity
+    ///
+    /// Each call to `next` is *O*(log(*n*)).
+    pub fn into_iter_sorted(self) -> IntoIterSorted<T, O, S> {
+        IntoIterSorted { inner: self }
+    }
+
+    /// Clears the heap, returning an iterator over the removed elements in heap order.
+    ///
+    /// If the iterator is dropped before being fully consumed,
+    /// it drops the remaining elements in heap order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mheap::{VecHeap, MinHeap};
+    ///
+    /// let mut heap = VecHeap::<_, MinHeap>::from([3, 1, 5, 2, 4]);
+    /// assert_eq!(heap.drain_sorted().take(3).collect::<Vec<_>>(), [1, 2, 3]);
+    /// assert!(heap.is_empty());
+    /// ```
+    ///
+    /// # Time complexity
+    ///
+    /// Each call to `next` is *O*(log(*n*)).
+    pub fn drain_sorted(&mut self) -> DrainSorted<'_, T, O, S> {
+        DrainSorted { inner: self }
+    }
+}
+
+impl<T, O: Default, S> Default for VecHeap<T, O, S> {
+    /// Creates an empty heap.
+    fn default() -> Self {
+        Self {
+            data: Shaped::new(Vec::new()),
+            ord: O::default(),
+        }
+    }
+}
+
+impl<T, O: Ordering<T> + Default, S: Shape> From<Vec<T>> for VecHeap<T, O, S> {
+    /// Converts a `Vec<T>` into a heap.
+    ///
+    /// This conversion happens in-place, and has *O*(*n*) time complexity.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mheap::{VecHeap, MaxHeap};
+    ///
+    /// let mut heap = VecHeap::<_, MaxHeap>::from(vec![3, 1, 5]);
+    /// assert_eq!(heap.pop(), Some(5));
+    /// ```
+    fn from(vec: Vec<T>) -> Self {
+        Self::from_vec_in_shape(vec, O::default())
+    }
+}
+
+impl<T, O: Ordering<T> + Default, S: Shape, const N: usize> From<[T; N]> for VecHeap<T, O, S> {
+    /// Converts a `[T; N]` into a heap.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mheap::{VecHeap, MinHeap};
+    ///
+    /// let mut heap = VecHeap::<_, MinHeap>::from([3, 1, 5]);
+    /// assert_eq!(heap.pop(), Some(1));
+    /// ```
+    fn from(arr: [T; N]) -> Self {
+        Self::from(Vec::from(arr))
+    }
+}
+
+impl<T, O: Ordering<T> + Default, S: Shape> FromIterator<T> for VecHeap<T, O, S> {
+    /// Collects the elements into a heap.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mheap::{VecHeap, MaxHeap};
+    ///
+    /// let mut heap: VecHeap<_, MaxHeap> = (1..=5).collect();
+    /// assert_eq!(heap.pop(), Some(5));
+    /// ```
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Self::from(iter.into_iter().collect::<Vec<_>>())
+    }
+}
+
+impl<T, O: Ordering<T>, S: Shape> Extend<T> for VecHeap<T, O, S> {
+    /// Pushes all elements of the iterator onto the heap.
+    ///
+    /// The heap invariant is restored once, after all elements were added.
+    /// Depending on the number of new elements, it either sifts up the new elements,
+    /// or rebuilds the whole heap.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mheap::{VecHeap, MaxHeap};
+    ///
+    /// let mut heap = VecHeap::<i32, MaxHeap>::new();
+    /// heap.push(3);
+    /// heap.extend([1, 7, 5]);
+    /// assert_eq!(heap.len(), 4);
+    /// assert_eq!(heap.pop(), Some(7));
+    /// ```
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        let guard = RebuildOnDrop::new(&mut self.data, &self.ord);
+        guard.data.extend(iter);
+    }
+}
+
+impl<'a, T: Copy + 'a, O: Ordering<T>, S: Shape> Extend<&'a T> for VecHeap<T, O, S> {
+    /// Pushes copies of all elements of the iterator onto the heap.
+    fn extend<I: IntoIterator<Item = &'a T>>(&mut self, iter: I) {
+        self.extend(iter.into_iter().copied());
+    }
 }
 
+impl<T, O, S> IntoIterator for VecHeap<T, O, S> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    /// Creates a consuming iterator, that is, one that moves each value out of
+    /// the heap in arbitrary order. The heap cannot be used after calling this.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mheap::{VecHeap, MaxHeap};
+    ///
+    /// let heap = VecHeap::<_, MaxHeap>::from([1, 2, 3, 4]);
+    /// let mut vec: Vec<_> = heap.into_iter().collect();
+    /// vec.sort();
+    /// assert_eq!(vec, [1, 2, 3, 4]);
+    /// ```
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter {
+            iter: self.data.into_inner().into_iter(),
+        }
+    }
+}
+
+impl<'a, T, O, S> IntoIterator for &'a VecHeap<T, O, S> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// An iterator over the elements of a [`VecHeap`], in arbitrary order.
+///
+/// This `struct` is created by [`VecHeap::iter`]. See its documentation for more.
+pub struct Iter<'a, T> {
+    iter: slice::Iter<'a, T>,
+}
+
+impl<T> Clone for Iter<'_, T> {
+    fn clone(&self) -> Self {
+        Self {
+            iter: self.iter.clone(),
+        }
+    }
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<T> DoubleEndedIterator for Iter<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.iter.next_back()
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+
+impl<T> FusedIterator for Iter<'_, T> {}
+
+/// An owning iterator over the elements of a [`VecHeap`], in arbitrary order.
+///
+/// This `struct` is created by [`VecHeap::into_iter`]
+/// (provided by the [`IntoIterator`] trait). See its documentation for more.
+pub struct IntoIter<T> {
+    iter: vec::IntoIter<T>,
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<T> DoubleEndedIterator for IntoIter<T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.iter.next_back()
+    }
+}
+
+impl<T> ExactSizeIterator for IntoIter<T> {}
+
+impl<T> FusedIterator for IntoIter<T> {}
+
+/// A draining iterator over the elements of a [`VecHeap`], in arbitrary order.
+///
+/// This `struct` is created by [`VecHeap::drain`]. See its documentation for more.
+pub struct Drain<'a, T> {
+    iter: vec::Drain<'a, T>,
+}
+
+impl<T> Iterator for Drain<'_, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<T> DoubleEndedIterator for Drain<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.iter.next_back()
+    }
+}
+
+impl<T> ExactSizeIterator for Drain<'_, T> {}
+
+impl<T> FusedIterator for Drain<'_, T> {}
+
+/// An iterator which removes the elements of a [`VecHeap`] matching a predicate.
+///
+/// This `struct` is created by [`VecHeap::extract_if`]. See its documentation for more.
+pub struct ExtractIf<'a, T, O: Ordering<T>, F, S: Shape = Binary> {
+    guard: RebuildOnDrop<'a, Shaped<Vec<T>, S>, O>,
+    pos: Position,
+    pred: F,
+}
+
+impl<T, O: Ordering<T>, F: FnMut(&T) -> bool, S: Shape> Iterator for ExtractIf<'_, T, O, F, S> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let data = &mut self.guard.data;
+        while self.pos < data.len() {
+            if (self.pred)(&data[self.pos]) {
+                // The order of the remaining elements does not matter, as we rebuild the tail anyway.
+                // The last element is moved to `pos`, so we visit it on the next iteration.
+                self.guard.rebuild_from = self.guard.rebuild_from.min(self.pos);
+                return Some(data.swap_remove(self.pos));
+            }
+            self.pos += 1;
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.guard.data.len() - self.pos))
+    }
+}
+
+impl<T, O: Ordering<T>, F: FnMut(&T) -> bool, S: Shape> FusedIterator for ExtractIf<'_, T, O, F, S> {}
+
+/// An owning iterator over the elements of a [`VecHeap`], in heap order.
+///
+/// This `struct` is created by [`VecHeap::into_iter_sorted`]. See its documentation for more.
+pub struct IntoIterSorted<T, O, S = Binary> {
+    inner: VecHeap<T, O, S>,
+}
+
+impl<T, O: Ordering<T>, S: Shape> Iterator for IntoIterSorted<T, O, S> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.pop()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.inner.len();
+        (len, Some(len))
+    }
+}
+
+impl<T, O: Ordering<T>, S: Shape> ExactSizeIterator for IntoIterSorted<T, O, S> {}
+
+impl<T, O: Ordering<T>, S: Shape> FusedIterator for IntoIterSorted<T, O, S> {}
+
+/// A draining iterator over the elements of a [`VecHeap`], in heap order.
+///
+/// This `struct` is created by [`VecHeap::drain_sorted`]. See its documentation for more.
+pub struct DrainSorted<'a, T, O: Ordering<T>, S: Shape = Binary> {
+    inner: &'a mut VecHeap<T, O, S>,
+}
+
+impl<T, O: Ordering<T>, S: Shape> Drop for DrainSorted<'_, T, O, S> {
+    /// Removes heap elements in heap order.
+    fn drop(&mut self) {
+        while self.inner.pop().is_some() {}
+    }
+}
+
+impl<T, O: Ordering<T>, S: Shape> Iterator for DrainSorted<'_, T, O, S> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.pop()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.inner.len();
+        (len, Some(len))
+    }
+}
+
+impl<T, O: Ordering<T>, S: Shape> ExactSizeIterator for DrainSorted<'_, T, O, S> {}
+
+impl<T, O: Ordering<T>, S: Shape> FusedIterator for DrainSorted<'_, T, O, S> {}
+
 /// Structure wrapping a mutable reference to the top item on a [`VecHeap`].
 ///
 /// This `struct` is created by the [`peek_mut`] method on [`VecHeap`]. See
 /// its documentation for more.
 ///
 /// [`peek_mut`]: VecHeap::peek_mut
-pub struct PeekMut<'a, T, O: Ordering<T>> {
-    raw: raw_heap::PeekMut<'a, Vec<T>>,
+pub struct PeekMut<'a, T, O: Ordering<T>, S: Shape = Binary> {
+    raw: raw_heap::PeekMut<'a, Shaped<Vec<T>, S>>,
     ord: &'a O,
 }
 
-impl<'a, T, O: Ordering<T>> Drop for PeekMut<'a, T, O> {
+impl<'a, T, O: Ordering<T>, S: Shape> Drop for PeekMut<'a, T, O, S> {
     fn drop(&mut self) {
         self.restore();
     }
 }
 
-impl<'a, T, O: Ordering<T>> Deref for PeekMut<'a, T, O> {
+impl<'a, T, O: Ordering<T>, S: Shape> Deref for PeekMut<'a, T, O, S> {
     type Target = T;
 
     fn deref(&self) -> &Self::Target {
-        self.raw.as_ref()
+        self.raw.item()
     }
 }
 
-impl<'a, T, O: Ordering<T>> DerefMut for PeekMut<'a, T, O> {
+impl<'a, T, O: Ordering<T>, S: Shape> DerefMut for PeekMut<'a, T, O, S> {
     fn deref_mut(&mut self) -> &mut Self::Target {
-        self.raw.as_mut()
+        self.raw.item_mut()
     }
 }
 
-impl<'a, T, O: Ordering<T>> PeekMut<'a, T, O> {
+impl<'a, T, O: Ordering<T>, S: Shape> PeekMut<'a, T, O, S> {
     fn restore(&mut self) {
         self.raw.restore(self.ord);
     }
@@ -1,4 +1,21 @@
-use mheap::{MaxHeap, MinHeap, IndexableHeap, VecHeap};
+use mheap::{
+    ArrayHeap, BoundedHeap, CachedKeyHeap, DenseIndexedHeap, IndexableCachedKeyHeap, IndexableHeap, IndexableMinMaxHeap, IndexableSoaHeap, KeyValueHeap, MaxHeap, MinHeap, MinMaxHeap, SoaHeap, StableHeap,
+    VecHeap,
+    bounded_heap::Policy,
+    ordering::{ByKey, ByTotalKey, NanPolicy, Natural, Reverse},
+    shape::{DAry, Shape},
+};
+
+/// A small deterministic pseudo-random generator for the randomized tests
+fn xorshift(seed: u32) -> impl FnMut() -> u32 {
+    let mut state = seed;
+    move || {
+        state ^= state << 13;
+        state ^= state >> 17;
+        state ^= state << 5;
+        state
+    }
+}
 
 #[test]
 fn min_heap() {
@@ -90,3 +107,1006 @@ fn unordered_heap_mut() {
     }
     assert_eq!(data, vec![1, 3, 1, 5, 6, 7, 15, 64]);
 }
+
+#[test]
+fn heapify() {
+    let input = vec![3, 15, 1, 42, 7, 6, 5, 64];
+
+    let mut heap = VecHeap::<i32, MinHeap>::from(input.clone());
+    let mut data = Vec::new();
+    while let Some(x) = heap.pop() {
+        data.push(x);
+    }
+    assert_eq!(data, vec![1, 3, 5, 6, 7, 15, 42, 64]);
+
+    let mut heap: VecHeap<i32, MaxHeap> = input.iter().copied().collect();
+    let mut data = Vec::new();
+    while let Some(x) = heap.pop() {
+        data.push(x);
+    }
+    assert_eq!(data, vec![64, 42, 15, 7, 6, 5, 3, 1]);
+}
+
+#[test]
+fn heapify_indices() {
+    let input = vec![3, 15, 1, 42, 7, 6, 5, 64];
+
+    let (mut heap, indices) = IndexableHeap::from_vec_with_ordering(input.clone(), MinHeap::natural());
+    for (idx, x) in indices.iter().zip(&input) {
+        assert_eq!(heap.by_index(*idx), x);
+    }
+
+    *heap.by_index_mut(indices[7]) = 0;
+    let mut data = Vec::new();
+    while let Some(x) = heap.pop() {
+        data.push(x);
+    }
+    assert_eq!(data, vec![0, 1, 3, 5, 6, 7, 15, 42]);
+}
+
+#[test]
+fn extend() {
+    let mut heap = VecHeap::<i32, MinHeap>::from([15, 3]);
+    heap.extend([1, 42, 7]);
+    heap.extend(&[6, 5, 64]);
+
+    let mut data = Vec::new();
+    while let Some(x) = heap.pop() {
+        data.push(x);
+    }
+    assert_eq!(data, vec![1, 3, 5, 6, 7, 15, 42, 64]);
+
+    let mut heap = IndexableHeap::<i32, MinHeap>::from([15, 3]);
+    heap.extend(&[1, 42]);
+    let indices = heap.extend_with_indices([7, 6, 5, 64]);
+    assert_eq!(heap.by_index(indices[3]), &64);
+
+    *heap.by_index_mut(indices[3]) = 2;
+    let mut data = Vec::new();
+    while let Some(x) = heap.pop() {
+        data.push(x);
+    }
+    assert_eq!(data, vec![1, 2, 3, 5, 6, 7, 15, 42]);
+}
+
+#[test]
+fn iterate() {
+    let input = [3, 15, 1, 42, 7, 6, 5, 64];
+    let mut heap = VecHeap::<i32, MaxHeap>::from(input);
+    assert_eq!(heap.iter().len(), input.len());
+    assert_eq!((&heap).into_iter().sum::<i32>(), input.iter().sum());
+    assert_eq!(heap.as_slice()[0], 64);
+
+    let mut drained: Vec<_> = heap.drain().collect();
+    assert!(heap.is_empty());
+    drained.sort();
+    assert_eq!(drained, [1, 3, 5, 6, 7, 15, 42, 64]);
+
+    heap.extend(input);
+    let mut owned: Vec<_> = heap.into_iter().collect();
+    owned.sort();
+    assert_eq!(owned, drained);
+}
+
+#[test]
+fn sorted() {
+    let input: Vec<_> = (0..100).map(|i| (i * 37) % 101).collect();
+    let mut expected = input.clone();
+    expected.sort();
+
+    let heap = VecHeap::<_, MinHeap>::from(input.clone());
+    assert_eq!(heap.into_sorted_vec(), expected);
+
+    let heap = VecHeap::from_vec_with_ordering(input.clone(), MinHeap::by_key(|x: &i32| -x));
+    let descending: Vec<_> = heap.into_iter_sorted().collect();
+    assert!(descending.iter().eq(expected.iter().rev()));
+
+    let mut heap = VecHeap::<_, MaxHeap>::from(input);
+    let top: Vec<_> = heap.drain_sorted().take(3).collect();
+    assert_eq!(top, [100, 99, 98]);
+    assert!(heap.is_empty());
+}
+
+#[test]
+fn retain() {
+    let input: Vec<_> = (0..100).map(|i| (i * 37) % 101).collect();
+
+    let mut heap = VecHeap::<_, MinHeap>::from(input.clone());
+    heap.retain(|x| x % 3 != 0);
+    let expected: Vec<_> = (0..=100).filter(|x| input.contains(x) && x % 3 != 0).collect();
+    assert_eq!(heap.into_sorted_vec(), expected);
+
+    let mut heap = VecHeap::<_, MaxHeap>::from(input.clone());
+    let mut extracted: Vec<_> = heap.extract_if(|x| x % 2 == 0).take(5).collect();
+    assert_eq!(extracted.len(), 5);
+    extracted.extend(heap.extract_if(|x| x % 2 == 0));
+    extracted.sort();
+    let expected: Vec<_> = (0..=100).filter(|x| input.contains(x) && x % 2 == 0).collect();
+    assert_eq!(extracted, expected);
+    assert!(heap.into_sorted_vec().windows(2).all(|w| w[0] > w[1] && w[1] % 2 == 1));
+}
+
+#[test]
+fn retain_panic() {
+    use std::panic::{AssertUnwindSafe, catch_unwind};
+
+    let input: Vec<_> = (0..100).map(|i| (i * 37) % 101).collect();
+    let mut heap = VecHeap::<_, MinHeap>::from(input);
+    let mut visited = 0;
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        heap.retain_mut(|x| {
+            visited += 1;
+            if visited == 50 {
+                panic!("predicate panicked");
+            }
+            *x = 100 - *x;
+            *x % 2 == 0
+        })
+    }));
+    assert!(result.is_err());
+
+    let sorted = heap.into_sorted_vec();
+    assert!(sorted.windows(2).all(|w| w[0] <= w[1]));
+}
+
+#[test]
+fn indexable_extend_panic() {
+    use std::panic::{AssertUnwindSafe, catch_unwind};
+
+    let mut heap = IndexableHeap::<i32, MinHeap>::from(vec![50, 60, 70]);
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        heap.extend((0..20).rev().map(|x| if x == 5 { panic!("iterator panicked") } else { x }));
+    }));
+    assert!(result.is_err());
+    assert_eq!(heap.peek(), Some(&6));
+
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        heap.extend_with_indices((0..3).rev().map(|x| if x == 0 { panic!("iterator panicked") } else { x }));
+    }));
+    assert!(result.is_err());
+    assert_eq!(heap.len(), 19);
+
+    let mut sorted = Vec::new();
+    while let Some(x) = heap.pop() {
+        sorted.push(x);
+    }
+    assert!(sorted.windows(2).all(|w| w[0] <= w[1]));
+    assert_eq!(sorted[0], 1);
+}
+
+#[test]
+fn remove_by_index() {
+    let mut heap = IndexableHeap::<i32, MinHeap>::new();
+    let indices: Vec<_> = [3, 15, 1, 42, 7, 6, 5, 64].into_iter().map(|x| heap.push(x)).collect();
+
+    assert_eq!(heap.remove(indices[3]), Some(42));
+    assert_eq!(heap.remove(indices[2]), Some(1));
+    assert!(!heap.contains(indices[3]));
+    assert_eq!(heap.get(indices[2]), None);
+    assert!(heap.get_mut(indices[2]).is_none());
+    assert_eq!(heap.get(indices[7]), Some(&64));
+
+    let mut data = Vec::new();
+    while let Some(x) = heap.pop() {
+        data.push(x);
+    }
+    assert_eq!(data, vec![3, 5, 6, 7, 15, 64]);
+    assert!(indices.iter().all(|idx| !heap.contains(*idx)));
+}
+
+#[test]
+fn pop_invalidates_index() {
+    let mut heap = IndexableHeap::<i32, MinHeap>::new();
+    let first = heap.push(1);
+    let second = heap.push(2);
+    let third = heap.push(3);
+
+    assert_eq!(heap.pop(), Some(1));
+    assert!(!heap.contains(first));
+    assert_eq!(heap.get(second), Some(&2));
+    assert_eq!(heap.get(third), Some(&3));
+
+    assert_eq!(heap.peek_mut().unwrap().pop(), 2);
+    assert!(!heap.contains(second));
+    assert_eq!(heap.get(third), Some(&3));
+}
+
+#[test]
+fn stale_index() {
+    let mut heap = IndexableHeap::<i32, MinHeap>::new();
+    let cancelled = heap.push(1);
+    heap.push(2);
+    assert_eq!(heap.remove(cancelled), Some(1));
+
+    // The freed slot is reused by the next push
+    let timer = heap.push(3);
+    assert_ne!(cancelled, timer);
+    assert!(!heap.contains(cancelled));
+    assert_eq!(heap.get(cancelled), None);
+    assert_eq!(heap.remove(cancelled), None);
+    assert_eq!(heap.get(timer), Some(&3));
+
+    assert_eq!(heap.pop(), Some(2));
+    assert_eq!(heap.pop(), Some(3));
+    assert!(!heap.contains(timer));
+    let last = heap.push(4);
+    assert_eq!(heap.get(timer), None);
+    assert_eq!(heap.get(last), Some(&4));
+}
+
+#[test]
+#[should_panic]
+fn stale_index_panics() {
+    let mut heap = IndexableHeap::<i32, MinHeap>::new();
+    let idx = heap.push(1);
+    heap.pop();
+    heap.push(2);
+    heap.by_index_mut(idx);
+}
+
+#[test]
+fn change_key() {
+    let mut heap = IndexableHeap::<i32, MinHeap>::new();
+    let indices: Vec<_> = [30, 150, 10, 420, 70, 60, 50, 640].into_iter().map(|x| heap.push(x)).collect();
+
+    assert_eq!(heap.decrease_key(indices[3], 1), Some(420));
+    assert_eq!(heap.increase_key(indices[2], 1000), Some(10));
+    assert_eq!(heap.update(indices[7], |x| x / 10), Some(640));
+    assert_eq!(heap.update(indices[0], |x| *x), Some(30));
+
+    let mut data = Vec::new();
+    while let Some(x) = heap.pop() {
+        data.push(x);
+    }
+    assert_eq!(data, vec![1, 30, 50, 60, 64, 70, 150, 1000]);
+}
+
+#[test]
+#[should_panic]
+#[cfg(debug_assertions)]
+fn decrease_key_wrong_direction() {
+    let mut heap = IndexableHeap::<i32, MinHeap>::new();
+    let idx = heap.push(1);
+    heap.push(2);
+    heap.decrease_key(idx, 3);
+}
+
+#[test]
+fn batch_update() {
+    // Both sparse updates (sifting dirty elements) and dense ones (full rebuild)
+    for step in [1, 7, 300] {
+        let mut heap = IndexableHeap::<i64, MinHeap>::new();
+        let indices: Vec<_> = (0..1000).map(|i| heap.push((i * 37) % 1009)).collect();
+
+        heap.batch_update(|batch| {
+            for idx in indices.iter().step_by(step) {
+                let x = batch.get_mut(*idx).unwrap();
+                *x = 1009 - *x * 3;
+            }
+        });
+
+        let mut expected: Vec<_> = (0..1000).map(|i| (i * 37) % 1009).collect();
+        for i in (0..1000).step_by(step) {
+            expected[i] = 1009 - expected[i] * 3;
+        }
+        expected.sort();
+
+        let mut data = Vec::new();
+        while let Some(x) = heap.pop() {
+            data.push(x);
+        }
+        assert_eq!(data, expected);
+    }
+}
+
+#[test]
+fn iterate_indexable() {
+    let input = [3, 15, 1, 42, 7, 6, 5, 64];
+    let mut heap = IndexableHeap::<i32, MaxHeap>::new();
+    let indices: Vec<_> = input.iter().map(|x| heap.push(*x)).collect();
+    heap.pop();
+
+    for (idx, x) in &heap {
+        assert_eq!(heap.get(idx), Some(x));
+    }
+    assert_eq!(heap.indices().count(), input.len() - 1);
+
+    let drained: Vec<_> = heap.drain().collect();
+    assert_eq!(drained.len(), input.len() - 1);
+    for (idx, x) in &drained {
+        let pos = indices.iter().position(|i| i == idx).unwrap();
+        assert_eq!(input[pos], *x);
+    }
+    assert!(indices.iter().all(|idx| !heap.contains(*idx)));
+
+    let again = heap.push(1);
+    assert!(indices.iter().all(|idx| *idx != again));
+    assert_eq!(heap.into_iter().collect::<Vec<_>>(), [(again, 1)]);
+}
+
+#[test]
+fn append_indexable() {
+    let mut a = IndexableHeap::<i32, MinHeap>::new();
+    let mut b = IndexableHeap::<i32, MinHeap>::new();
+    let a_indices: Vec<_> = [3, 15, 1, 42].into_iter().map(|x| a.push(x)).collect();
+    let b_indices: Vec<_> = [7, 6, 5, 64].into_iter().map(|x| b.push(x)).collect();
+
+    let remap = a.append(&mut b);
+    assert!(b.is_empty());
+    assert_eq!(remap.len(), 4);
+    for (old, new) in &remap {
+        let pos = b_indices.iter().position(|idx| idx == old).unwrap();
+        assert_eq!(a.get(*new), Some(&[7, 6, 5, 64][pos]));
+        assert!(!b.contains(*old));
+    }
+    assert_eq!(a.get(a_indices[1]), Some(&15));
+
+    let mut data = Vec::new();
+    while let Some(x) = a.pop() {
+        data.push(x);
+    }
+    assert_eq!(data, vec![1, 3, 5, 6, 7, 15, 42, 64]);
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn keyed_heap() {
+    use mheap::{KeyedHeap, keyed_heap::Entry};
+
+    let mut queue = KeyedHeap::<String, u32, MinHeap>::new();
+    for (i, name) in ["a", "b", "c", "d", "e"].into_iter().enumerate() {
+        assert_eq!(queue.push_or_update(name.to_string(), 10 * i as u32), None);
+    }
+    assert_eq!(queue.push_or_update("e".to_string(), 5), Some(40));
+    assert_eq!(queue.remove("a"), Some(0));
+    assert_eq!(queue.remove("a"), None);
+    assert_eq!(queue.priority_of("b"), Some(&10));
+
+    match queue.entry("c".to_string()) {
+        Entry::Occupied(entry) => assert_eq!(entry.remove(), ("c".to_string(), 20)),
+        Entry::Vacant(_) => unreachable!(),
+    }
+    queue.entry("f".to_string()).and_modify(|p| *p = 0).or_insert(1);
+    queue.entry("d".to_string()).and_modify(|p| *p = 0).or_insert(1);
+    assert_eq!(queue.len(), 4);
+
+    let mut data = Vec::new();
+    while let Some((key, priority)) = queue.pop() {
+        assert!(!queue.contains_key(&key));
+        data.push((key, priority));
+    }
+    let expected = [("d", 0), ("f", 1), ("e", 5), ("b", 10)];
+    assert!(data.iter().map(|(k, p)| (k.as_str(), *p)).eq(expected));
+}
+
+#[test]
+fn btree_keyed_heap() {
+    use mheap::{BTreeKeyedHeap, btree_keyed_heap::Entry};
+
+    // Not `Hash`, only `Ord`
+    #[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Debug)]
+    struct Node(u32);
+
+    let mut queue = BTreeKeyedHeap::<Node, u32, MaxHeap>::new();
+    for i in 0..5 {
+        assert_eq!(queue.push_or_update(Node(i), i * 10), None);
+    }
+    assert_eq!(queue.push_or_update(Node(0), 100), Some(0));
+    assert_eq!(queue.remove(&Node(3)), Some(30));
+    assert_eq!(queue.priority_of(&Node(1)), Some(&10));
+
+    match queue.entry(Node(4)) {
+        Entry::Occupied(mut entry) => assert_eq!(entry.set_priority(5), 40),
+        Entry::Vacant(_) => unreachable!(),
+    }
+    assert_eq!(queue.peek(), Some((&Node(0), &100)));
+
+    let mut data = Vec::new();
+    while let Some((key, priority)) = queue.pop() {
+        assert!(!queue.contains_key(&key));
+        data.push((key.0, priority));
+    }
+    assert_eq!(data, vec![(0, 100), (2, 20), (1, 10), (4, 5)]);
+}
+
+#[test]
+#[should_panic = "id is too large"]
+fn dense_id_overflow() {
+    let mut heap = DenseIndexedHeap::<u32, MinHeap>::new();
+    heap.push(usize::MAX, 1);
+}
+
+#[test]
+fn dense_dijkstra() {
+    // (from, to, weight)
+    let edges = [
+        (0, 1, 7),
+        (0, 2, 9),
+        (0, 5, 14),
+        (1, 2, 10),
+        (1, 3, 15),
+        (2, 3, 11),
+        (2, 5, 2),
+        (3, 4, 6),
+        (4, 5, 9),
+    ];
+    let n = 6;
+
+    let mut dist = vec![u32::MAX; n];
+    let mut heap = DenseIndexedHeap::<u32, MinHeap>::with_capacity(n);
+    dist[0] = 0;
+    heap.push(0, 0);
+    while let Some((u, d)) = heap.pop() {
+        for &(a, b, w) in &edges {
+            let v = if a == u {
+                b
+            } else if b == u {
+                a
+            } else {
+                continue;
+            };
+            if d + w < dist[v] {
+                if heap.contains(v) {
+                    heap.decrease_key(v, d + w);
+                } else {
+                    heap.push(v, d + w);
+                }
+                dist[v] = d + w;
+            }
+        }
+    }
+    assert_eq!(dist, [0, 7, 9, 20, 20, 11]);
+    assert!(heap.is_empty());
+
+    heap.push(4, 1);
+    heap.push(2, 5);
+    assert_eq!(heap.remove(4), Some(1));
+    assert_eq!(heap.remove(4), None);
+    assert_eq!(heap.peek(), Some((2, &5)));
+}
+
+fn check_dary<S: Shape>() {
+    // Shuffled, but deterministic data
+    let data: Vec<u64> = (0..200u64).map(|x| x.wrapping_mul(6364136223846793005) % 1000).collect();
+    let mut sorted = data.clone();
+    sorted.sort();
+
+    let heap = VecHeap::<_, MinHeap, S>::from(data.clone());
+    assert_eq!(heap.into_sorted_vec(), sorted);
+
+    let mut heap = VecHeap::<_, MinHeap, S>::new();
+    heap.extend(data.iter().copied());
+    let popped: Vec<_> = std::iter::from_fn(|| heap.pop()).collect();
+    assert_eq!(popped, sorted);
+
+    let mut heap = IndexableHeap::<_, MinHeap, S>::new();
+    let indices: Vec<_> = data.iter().map(|&x| heap.push(x)).collect();
+    for (i, idx) in indices.iter().enumerate().step_by(3) {
+        heap.remove(*idx);
+        if let Some(next) = indices.get(i + 1) {
+            heap.update(*next, |x| x + 500);
+        }
+    }
+    let mut expected: Vec<_> = heap.iter().map(|(_, &x)| x).collect();
+    expected.sort();
+    let popped: Vec<_> = std::iter::from_fn(|| heap.pop()).collect();
+    assert_eq!(popped, expected);
+}
+
+#[test]
+fn dary_heaps() {
+    check_dary::<DAry<2>>();
+    check_dary::<DAry<3>>();
+    check_dary::<DAry<4>>();
+    check_dary::<DAry<8>>();
+}
+
+#[test]
+fn min_max_heap() {
+    let mut rng = xorshift(12345);
+    let mut next = move || rng() % 100;
+
+    let mut heap = MinMaxHeap::new();
+    let mut model = Vec::new();
+    for step in 0..2000 {
+        match next() % 6 {
+            0..=2 => {
+                let x = next();
+                heap.push(x);
+                model.push(x);
+            }
+            3 => {
+                let min = model.iter().min().copied();
+                if let Some(min) = min {
+                    model.remove(model.iter().position(|&x| x == min).unwrap());
+                }
+                assert_eq!(heap.pop_min(), min, "step {step}");
+            }
+            4 => {
+                let max = model.iter().max().copied();
+                if let Some(max) = max {
+                    model.remove(model.iter().position(|&x| x == max).unwrap());
+                }
+                assert_eq!(heap.pop_max(), max, "step {step}");
+            }
+            _ => {
+                let x = next();
+                let peek = if step % 2 == 0 { heap.peek_min_mut() } else { heap.peek_max_mut() };
+                if let Some(mut peek) = peek {
+                    let pos = model.iter().position(|y| y == &*peek).unwrap();
+                    model[pos] = x;
+                    *peek = x;
+                }
+            }
+        }
+        assert_eq!(heap.len(), model.len());
+        assert_eq!(heap.peek_min(), model.iter().min());
+        assert_eq!(heap.peek_max(), model.iter().max());
+    }
+
+    model.sort();
+    assert_eq!(heap.into_sorted_vec(), model);
+
+    let heap = MinMaxHeap::<_>::from((0..100).rev().collect::<Vec<_>>());
+    assert_eq!(heap.into_sorted_vec(), (0..100).collect::<Vec<_>>());
+}
+
+#[test]
+fn min_max_heap_cmp() {
+    let mut heap = MinMaxHeap::with_cmp(ByKey::new(|x: &(u32, u32)| x.0).reverse().then_by_key(|x: &(u32, u32)| x.1));
+    heap.extend([(1, 5), (3, 1), (3, 2), (1, 4)]);
+    assert_eq!(heap.pop_min(), Some((3, 1)));
+    assert_eq!(heap.pop_max(), Some((1, 5)));
+
+    let heap: MinMaxHeap<_, Reverse<Natural>> = (0..10).collect();
+    assert_eq!(heap.peek_min(), Some(&9));
+    assert_eq!(heap.peek_max(), Some(&0));
+
+    let (mut heap, indices) = IndexableMinMaxHeap::from_vec_with_cmp(vec![0.5, -2.0, 3.0], ByTotalKey::new(|x: &f64| *x));
+    assert_eq!(heap.peek_min(), Some(&-2.0));
+    heap.update(indices[0], |_| 4.0);
+    assert_eq!(heap.pop_max(), Some(4.0));
+
+    let heap = IndexableMinMaxHeap::<_, Reverse<Natural>>::from([2, 7, 1]);
+    assert_eq!(heap.peek_min(), Some(&7));
+}
+
+#[test]
+fn indexable_min_max_heap() {
+    let mut heap = IndexableMinMaxHeap::by_key(|x: &(u32, i32)| x.1);
+    let indices: Vec<_> = (0..50).map(|i| heap.push((i, (i as i32 * 37) % 50))).collect();
+
+    for (i, idx) in indices.iter().enumerate() {
+        if i % 3 == 0 {
+            assert_eq!(heap.remove(*idx).map(|x| x.0), Some(i as u32));
+        } else if i % 3 == 1 {
+            heap.update(*idx, |&(i, x)| (i, 100 - x));
+        }
+    }
+    assert!(!heap.contains(indices[0]));
+    assert_eq!(heap.get(indices[1]), Some(&(1, 63)));
+
+    let mut expected: Vec<_> = indices.iter().filter_map(|idx| heap.get(*idx)).map(|x| x.1).collect();
+    expected.sort();
+    let mut popped = Vec::new();
+    while let Some(min) = heap.pop_min() {
+        popped.push(min.1);
+        if let Some(max) = heap.pop_max() {
+            popped.push(max.1);
+        }
+    }
+    let (mut mins, mut maxs) = (Vec::new(), Vec::new());
+    for (i, x) in popped.into_iter().enumerate() {
+        if i % 2 == 0 { mins.push(x) } else { maxs.push(x) }
+    }
+    maxs.reverse();
+    mins.extend(maxs);
+    assert_eq!(mins, expected);
+}
+
+#[test]
+fn bounded_heap() {
+    let data: Vec<i32> = (0..200).map(|i| (i * 73) % 101).collect();
+
+    let mut top = BoundedHeap::<_, MaxHeap>::new(10);
+    for &x in &data {
+        let was_full = top.is_full();
+        assert_eq!(top.offer(x).is_some(), was_full);
+        assert!(top.len() <= 10);
+    }
+    let mut expected = data.clone();
+    expected.sort_by(|a, b| b.cmp(a));
+    expected.truncate(10);
+    assert_eq!(top.peek_best(), expected.first());
+    assert_eq!(top.threshold(), expected.last());
+    assert_eq!(top.into_sorted_vec(), expected);
+
+    let mut bottom = BoundedHeap::<_, MaxHeap>::with_policy(10, Policy::EvictBest);
+    bottom.extend(data.iter().copied());
+    let mut expected = data.clone();
+    expected.sort_by(|a, b| b.cmp(a));
+    expected.drain(..expected.len() - 10);
+    assert_eq!(bottom.threshold(), expected.first());
+    assert_eq!(bottom.into_sorted_vec(), expected);
+
+    let mut first = BoundedHeap::with_ordering_and_policy(3, MinHeap::by_key(|x: &(i32, i32)| x.0), Policy::RejectNew);
+    assert_eq!(first.offer((5, 0)), None);
+    assert_eq!(first.offer((1, 1)), None);
+    assert_eq!(first.threshold(), None);
+    assert_eq!(first.offer((3, 2)), None);
+    assert_eq!(first.threshold(), None);
+    assert_eq!(first.offer((0, 3)), Some((0, 3)));
+    assert_eq!(first.into_sorted_vec(), [(1, 1), (3, 2), (5, 0)]);
+
+    // Ties keep the element, that was offered first
+    let mut ties = BoundedHeap::with_ordering(1, MaxHeap::by_key(|x: &(i32, i32)| x.0));
+    assert_eq!(ties.offer((1, 0)), None);
+    assert_eq!(ties.offer((1, 1)), Some((1, 1)));
+    assert_eq!(ties.offer((2, 2)), Some((1, 0)));
+
+    let mut empty = BoundedHeap::<_, MinHeap>::new(0);
+    assert_eq!(empty.offer(1), Some(1));
+    assert!(empty.is_empty() && empty.is_full());
+    assert_eq!(empty.threshold(), None);
+}
+
+#[test]
+fn ordering_combinators() {
+    // Deadline ascending, then priority descending, then insertion order
+    let tasks = [(3, 1, 0), (1, 1, 1), (3, 5, 2), (1, 1, 3), (2, 9, 4), (1, 4, 5)];
+    let ord = MinHeap::by_key(|t: &(u32, u32, u32)| t.0)
+        .then_by(|a: &(u32, u32, u32), b| b.1.cmp(&a.1))
+        .then_by_key(|t: &(u32, u32, u32)| t.2);
+    let mut heap = VecHeap::with_ordering(ord);
+    heap.extend(tasks);
+    let order: Vec<_> = std::iter::from_fn(|| heap.pop()).map(|t| t.2).collect();
+    assert_eq!(order, [5, 1, 3, 4, 2, 0]);
+
+    // The same ordering, built from the comparisons, and reversed for a max heap
+    let cmp = ByKey::new(|t: &(u32, u32, u32)| t.0)
+        .then(ByKey::new(|t: &(u32, u32, u32)| t.1).reverse())
+        .then_by_key(|t: &(u32, u32, u32)| t.2)
+        .reverse();
+    let mut heap = VecHeap::with_ordering(MaxHeap::with_cmp(cmp));
+    heap.extend(tasks);
+    let order: Vec<_> = std::iter::from_fn(|| heap.pop()).map(|t| t.2).collect();
+    assert_eq!(order, [5, 1, 3, 4, 2, 0]);
+
+    let mut heap = VecHeap::<i32, MinHeap<Reverse<Natural>>>::new();
+    heap.extend([1, 4, 2]);
+    assert_eq!(heap.pop(), Some(4));
+}
+
+#[test]
+fn float_keys() {
+    let dists = [2.5f32, -0.0, 7.0, 0.0, f32::INFINITY, -3.0];
+
+    let mut heap = VecHeap::with_ordering(MinHeap::by_total_key(|x: &(usize, f32)| x.1));
+    heap.extend(dists.iter().copied().enumerate());
+    let order: Vec<_> = std::iter::from_fn(|| heap.pop()).map(|x| x.0).collect();
+    assert_eq!(order, [5, 1, 3, 0, 2, 4]);
+
+    let mut heap = VecHeap::with_ordering(MaxHeap::by_partial_key(|x: &f64| *x, NanPolicy::Last));
+    heap.extend([1.0, f64::NAN, -2.0, f64::NAN, 4.0]);
+    assert!(heap.pop().unwrap().is_nan());
+    assert!(heap.pop().unwrap().is_nan());
+    assert_eq!(heap.into_sorted_vec(), [4.0, 1.0, -2.0]);
+
+    let mut heap = VecHeap::with_ordering(MaxHeap::by_partial_key(|x: &f64| *x, NanPolicy::First));
+    heap.extend([1.0, f64::NAN, -2.0, 4.0]);
+    assert_eq!(heap.pop(), Some(4.0));
+    assert_eq!(heap.pop(), Some(1.0));
+    assert_eq!(heap.pop(), Some(-2.0));
+    assert!(heap.pop().unwrap().is_nan());
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic = "compared a NaN key"]
+fn float_keys_nan_panic() {
+    let mut heap = VecHeap::with_ordering(MinHeap::by_partial_key(|x: &f64| *x, NanPolicy::Panic));
+    heap.extend([1.0, f64::NAN]);
+}
+
+#[test]
+fn stable_heap() {
+    let mut heap = StableHeap::with_ordering(MaxHeap::by_key(|job: &(u32, usize)| job.0));
+    let mut expected = Vec::new();
+    for i in 0..300 {
+        let job = ((i as u32 * 7) % 5, i);
+        heap.push(job);
+        expected.push(job);
+    }
+    // Sorting is stable, so equal priorities keep the insertion order
+    expected.sort_by_key(|job| std::cmp::Reverse(job.0));
+
+    for job in expected.drain(..100) {
+        assert_eq!(heap.pop(), Some(job));
+    }
+
+    // Elements pushed later queue behind the equal ones
+    let enqueue = |expected: &mut Vec<(u32, usize)>, job: (u32, usize)| {
+        let pos = expected.iter().position(|it| it.0 < job.0).unwrap_or(expected.len());
+        expected.insert(pos, job);
+    };
+    heap.push((4, 1000));
+    enqueue(&mut expected, (4, 1000));
+    heap.push((1, 1001));
+    enqueue(&mut expected, (1, 1001));
+
+    // A modified element keeps its stamp
+    if let Some(mut top) = heap.peek_mut() {
+        assert_eq!(*top, expected[0]);
+        top.0 = 1;
+    }
+    expected.remove(0);
+    let pos = expected.iter().position(|it| it.0 < 1 || it.0 == 1 && it.1 > 1000).unwrap();
+    expected.insert(pos, (1, 1000));
+
+    assert_eq!(heap.len(), expected.len());
+    assert_eq!(heap.into_sorted_vec(), expected);
+}
+
+#[test]
+fn key_value_heap() {
+    // The values are not comparable
+    struct Payload(usize);
+
+    let keys: Vec<u32> = (0..100).map(|i| (i * 37) % 50).collect();
+    let mut heap = KeyValueHeap::<u32, Payload, MaxHeap>::new();
+    for (i, &key) in keys.iter().enumerate() {
+        heap.push(key, Payload(i));
+    }
+    assert_eq!(heap.len(), 100);
+
+    if let Some((&key, value)) = heap.peek_mut() {
+        assert_eq!(key, 49);
+        value.0 += 1000;
+    }
+    let (key, value) = heap.pop().unwrap();
+    assert_eq!(key, 49);
+    assert!(value.0 >= 1000 && keys[value.0 - 1000] == 49);
+
+    let mut sorted = keys.clone();
+    sorted.sort_by_key(|&key| std::cmp::Reverse(key));
+    let popped: Vec<_> = heap.into_sorted_vec().into_iter().map(|(key, value)| {
+        assert_eq!(keys[value.0], key);
+        key
+    }).collect();
+    assert_eq!(popped, sorted[1..]);
+
+    let heap: KeyValueHeap<_, _, MinHeap> = keys.iter().map(|&key| (key, Payload(key as usize))).collect();
+    assert_eq!(heap.peek().map(|(key, value)| (*key, value.0)), Some((0, 0)));
+}
+
+#[test]
+fn soa_heaps() {
+    let mut next = xorshift(0x2545_f491);
+
+    let mut heap = SoaHeap::<u32, String, MinHeap>::new();
+    let mut model = Vec::new();
+    for step in 0..2000 {
+        if next().is_multiple_of(3) {
+            let top = heap.pop();
+            let min = model.iter().map(|(key, _)| *key).min();
+            assert_eq!(top.as_ref().map(|(key, _)| *key), min, "step {step}");
+            if let Some((key, value)) = top {
+                let pos = model.iter().position(|item| item.0 == key && item.1 == value).unwrap();
+                model.swap_remove(pos);
+            }
+        } else {
+            let key = next() % 100;
+            heap.push(key, format!("{key}-{step}"));
+            model.push((key, format!("{key}-{step}")));
+        }
+        assert_eq!(heap.len(), model.len());
+    }
+    if let Some((_, value)) = heap.peek_mut() {
+        value.push('!');
+    }
+    assert!(heap.peek().unwrap().1.ends_with('!'));
+    assert_eq!(heap.iter().count(), model.len());
+    let sorted = heap.into_sorted_vec();
+    assert!(sorted.windows(2).all(|w| w[0].0 <= w[1].0));
+
+    let mut heap = IndexableSoaHeap::<u32, String, MaxHeap>::new();
+    let indices: Vec<_> = (0..60).map(|i| heap.push(i * 7 % 60, i.to_string())).collect();
+    for (i, &idx) in indices.iter().enumerate() {
+        match i % 4 {
+            0 => assert_eq!(heap.remove(idx).map(|(_, value)| value), Some(i.to_string())),
+            1 => assert!(heap.update_key(idx, 100 + i as u32).is_some()),
+            2 => heap.value_mut(idx).unwrap().push('*'),
+            _ => {}
+        }
+    }
+    assert!(!heap.contains(indices[0]));
+    assert_eq!(heap.remove(indices[0]), None);
+    assert_eq!(heap.get(indices[2]), Some((&14, &"2*".to_string())));
+
+    let reused = heap.push(0, "reused".to_string());
+    assert!(!heap.contains(indices[0]) && heap.contains(reused));
+
+    let sorted = heap.into_sorted_vec();
+    assert_eq!(sorted.len(), 46);
+    assert_eq!(sorted[0], (157, "57".to_string()));
+    assert!(sorted.windows(2).all(|w| w[0].0 >= w[1].0));
+}
+
+#[test]
+fn soa_heaps_api() {
+    let mut heap = SoaHeap::<u32, char, MinHeap, DAry<4>>::from(vec![(5, 'e'), (1, 'a'), (3, 'c'), (4, 'd'), (2, 'b')]);
+    heap.push(0, 'z');
+    assert_eq!(heap.into_sorted_vec().iter().map(|(_, v)| *v).collect::<String>(), "zabcde");
+
+    let (mut heap, indices) = IndexableSoaHeap::from_vec_with_ordering((0..50u32).map(|i| (i, i)).collect(), MinHeap::natural());
+    assert_eq!(heap.decrease_key(indices[40], 0), Some(40));
+    assert_eq!(heap.peek(), Some((&0, &0)));
+    assert_eq!(heap.increase_key(indices[0], 100), Some(0));
+    assert_eq!(heap.peek(), Some((&0, &40)));
+
+    heap.batch_update(|batch| {
+        for idx in &indices[10..20] {
+            *batch.key_mut(*idx).unwrap() += 1000;
+            *batch.value_mut(*idx).unwrap() += 1;
+        }
+        assert_eq!(batch.get(indices[10]), Some((&1010, &11)));
+    });
+    let sorted = heap.into_sorted_vec();
+    assert!(sorted.windows(2).all(|w| w[0].0 <= w[1].0));
+    assert_eq!(sorted.last(), Some(&(1019, 20)));
+
+    let heap: IndexableSoaHeap<_, _, MaxHeap, DAry<3>> = (0..20).map(|i| (i % 7, i)).collect();
+    assert_eq!(heap.peek().map(|(key, _)| *key), Some(6));
+}
+
+#[test]
+fn cached_key_heap() {
+    let calls = std::cell::Cell::new(0);
+    let key = |s: &String| {
+        calls.set(calls.get() + 1);
+        s.parse::<i32>().unwrap()
+    };
+    let mut heap = CachedKeyHeap::with_ordering(MinHeap::by_cached_key(key));
+    let values: Vec<i32> = (0..200).map(|i| (i * 61) % 200 - 100).collect();
+    heap.extend(values.iter().map(|x| x.to_string()));
+    assert_eq!(calls.get(), 200);
+
+    // A mutation recomputes the key once, even for the last element
+    if let Some(mut top) = heap.peek_mut() {
+        assert_eq!(*top, "-100");
+        *top = "1000".to_string();
+    }
+    assert_eq!(calls.get(), 201);
+    if let Some(top) = heap.peek_mut() {
+        assert_eq!(*top, "-99");
+    }
+    assert_eq!(calls.get(), 201);
+    assert_eq!(heap.peek_mut().map(|top| top.pop()).as_deref(), Some("-99"));
+
+    let mut expected: Vec<_> = values.iter().copied().filter(|&x| x > -99).chain([1000]).collect();
+    expected.sort();
+    let sorted: Vec<i32> = heap.into_sorted_vec().iter().map(|s| s.parse().unwrap()).collect();
+    assert_eq!(sorted, expected);
+    assert_eq!(calls.get(), 201);
+
+    let mut single = CachedKeyHeap::with_ordering(MaxHeap::by_cached_key(|v: &Vec<u32>| v.len()));
+    single.push(vec![1, 2, 3]);
+    if let Some(mut top) = single.peek_mut() {
+        top.clear();
+    }
+    assert_eq!(single.peek_key(), Some(&0));
+}
+
+#[test]
+fn indexable_cached_key_heap() {
+    let calls = std::cell::Cell::new(0);
+    let key = |s: &String| {
+        calls.set(calls.get() + 1);
+        s.parse::<i32>().unwrap()
+    };
+    let mut heap = IndexableCachedKeyHeap::with_ordering(MaxHeap::by_cached_key(key));
+    let indices: Vec<_> = (0..50).map(|i| heap.push(((i * 17) % 50).to_string())).collect();
+    assert_eq!(calls.get(), 50);
+
+    // Reading through `GetMut` does not recompute the key
+    assert_eq!(*heap.by_index_mut(indices[3]), "1");
+    assert_eq!(calls.get(), 50);
+
+    // Mutations recompute the key once, and move the element up or down
+    *heap.by_index_mut(indices[3]) = "100".to_string();
+    if let Some(mut entry) = heap.get_mut(indices[0]) {
+        *entry = "-1".to_string();
+    }
+    assert_eq!(calls.get(), 52);
+    assert_eq!(heap.get_key(indices[3]), Some(&100));
+    assert_eq!(heap.peek().map(String::as_str), Some("100"));
+
+    assert_eq!(heap.remove(indices[5]).as_deref(), Some("35"));
+    assert!(!heap.contains(indices[5]));
+    assert_eq!(heap.by_index_mut(indices[6]).remove(), "2");
+    assert_eq!(heap.len(), 48);
+
+    let mut popped = Vec::new();
+    while let Some(item) = heap.pop() {
+        popped.push(item.parse::<i32>().unwrap());
+    }
+    assert!(indices.iter().all(|&idx| heap.get(idx).is_none()));
+    let mut expected: Vec<_> = (0..50).map(|i| (i * 17) % 50).filter(|&x| ![0, 1, 35, 2].contains(&x)).chain([100, -1]).collect();
+    expected.sort_by(|a, b| b.cmp(a));
+    assert_eq!(popped, expected);
+    assert_eq!(calls.get(), 52);
+}
+
+#[test]
+fn cached_key_panic() {
+    use std::panic::{AssertUnwindSafe, catch_unwind};
+
+    let mut heap = CachedKeyHeap::with_ordering(MinHeap::by_cached_key(|s: &String| s.parse::<u32>().unwrap()));
+    heap.extend((0..20).map(|x| ((x * 7) % 20).to_string()));
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        if let Some(mut top) = heap.peek_mut() {
+            *top = "not a number".to_string();
+        }
+    }));
+    assert!(result.is_err());
+
+    // The mutated element keeps its old key at the root
+    let mut keys = Vec::new();
+    while let Some(&key) = heap.peek_key() {
+        keys.push(key);
+        heap.pop();
+    }
+    assert_eq!(keys, (0..20).collect::<Vec<_>>());
+}
+
+#[test]
+fn array_heap() {
+    let mut heap = ArrayHeap::<u32, MaxHeap, 16>::new();
+    let mut model = Vec::new();
+    for i in 0..100u32 {
+        let x = (i * 29) % 37;
+        if i % 3 == 2 {
+            assert_eq!(heap.pop(), model.iter().max().copied());
+            if let Some(pos) = model.iter().position(|&y| Some(&y) == model.iter().max()) {
+                model.swap_remove(pos);
+            }
+        } else if model.len() < 16 {
+            assert_eq!(heap.try_push(x), Ok(()));
+            model.push(x);
+        } else {
+            assert!(heap.is_full());
+            assert_eq!(heap.try_push(x), Err(x));
+        }
+        assert_eq!(heap.len(), model.len());
+        assert_eq!(heap.peek(), model.iter().max());
+    }
+
+    if let Some(mut top) = heap.peek_mut() {
+        *top = 0;
+    }
+    let max = model.iter_mut().max().unwrap();
+    *max = 0;
+    let mut sorted = model.clone();
+    sorted.sort_by(|a, b| b.cmp(a));
+    let popped: Vec<_> = std::iter::from_fn(|| heap.pop()).collect();
+    assert_eq!(popped, sorted);
+
+    // Every element is dropped exactly once
+    let rc = std::rc::Rc::new(());
+    let mut heap = ArrayHeap::<_, _, 8>::with_ordering(MinHeap::by_key(|x: &(u32, std::rc::Rc<()>)| x.0));
+    for i in 0..8 {
+        heap.try_push((i, rc.clone())).unwrap();
+    }
+    assert!(heap.try_push((8, rc.clone())).is_err());
+    assert_eq!(std::rc::Rc::strong_count(&rc), 9);
+    heap.peek_mut().unwrap().pop();
+    heap.pop();
+    assert_eq!(std::rc::Rc::strong_count(&rc), 7);
+    heap.clear();
+    assert_eq!(std::rc::Rc::strong_count(&rc), 1);
+    for i in 0..5 {
+        heap.try_push((i, rc.clone())).unwrap();
+    }
+    drop(heap);
+    assert_eq!(std::rc::Rc::strong_count(&rc), 1);
+}